@@ -0,0 +1,93 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::errors::ZoteroError;
+use crate::library::Library;
+
+/// What an API key may do in a library.
+///
+/// Keys only report `library` and `write` access for groups. Group notes are
+/// readable with library access and group files are writable with write
+/// access, so `notes` and `files` follow those two flags there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Permissions {
+    pub library: bool,
+    pub notes: bool,
+    pub write: bool,
+    pub files: bool,
+}
+
+impl Permissions {
+    fn user(access: &Value) -> Self {
+        let flag = |name: &str| access[name].as_bool().unwrap_or(false);
+        Permissions {
+            library: flag("library"),
+            notes: flag("notes"),
+            write: flag("write"),
+            files: flag("files"),
+        }
+    }
+
+    fn group(access: &Value) -> Self {
+        let library = access["library"].as_bool().unwrap_or(false);
+        let write = access["write"].as_bool().unwrap_or(false);
+        Permissions {
+            library,
+            notes: library,
+            write,
+            files: write,
+        }
+    }
+}
+
+/// A library an API key can read, with a client pointed at it.
+#[derive(Debug, Clone)]
+pub struct AccessibleLibrary<C> {
+    pub library: Library,
+    /// User name or group name.
+    pub name: String,
+    pub permissions: Permissions,
+    pub client: C,
+}
+
+/// Access rules of an API key, parsed from its key info.
+pub(crate) struct KeyAccess {
+    pub user_id: u64,
+    pub username: String,
+    user: Permissions,
+    all_groups: Permissions,
+    groups: BTreeMap<u64, Permissions>,
+}
+
+impl KeyAccess {
+    pub fn from_key_info(info: &Value) -> Result<Self, ZoteroError> {
+        let user_id = info["userID"]
+            .as_u64()
+            .ok_or_else(|| ZoteroError::InvalidResponse("key info has no userID".to_string()))?;
+        let access = &info["access"];
+        let mut groups = BTreeMap::new();
+        if let Some(rules) = access["groups"].as_object() {
+            for (id, rule) in rules {
+                if let Ok(id) = id.parse::<u64>() {
+                    groups.insert(id, Permissions::group(rule));
+                }
+            }
+        }
+        Ok(KeyAccess {
+            user_id,
+            username: info["username"].as_str().unwrap_or_default().to_string(),
+            user: Permissions::user(&access["user"]),
+            all_groups: Permissions::group(&access["groups"]["all"]),
+            groups,
+        })
+    }
+
+    pub fn user(&self) -> Permissions {
+        self.user
+    }
+
+    /// Rules for a specific group take precedence over the `all` rule.
+    pub fn group(&self, id: u64) -> Permissions {
+        self.groups.get(&id).copied().unwrap_or(self.all_groups)
+    }
+}
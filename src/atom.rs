@@ -0,0 +1,213 @@
+use reqwest::Url;
+use roxmltree::{Document, Node};
+use serde_json::Value;
+
+use crate::errors::ZoteroError;
+use crate::models::{Link, Links};
+use crate::pagination::PageLinks;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+const ZAPI_NS: &str = "http://zotero.org/ns/api";
+
+/// A `format=atom` response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub id: String,
+    pub title: String,
+    pub updated: Option<String>,
+    /// `zapi:totalResults`, the number of results across all pages.
+    pub total_results: Option<u64>,
+    /// Pagination links of the feed.
+    pub links: PageLinks,
+    pub entries: Vec<Entry>,
+}
+
+impl Feed {
+    pub fn from_xml(xml: &str) -> Result<Self, ZoteroError> {
+        let document = Document::parse(xml)?;
+        let root = document.root_element();
+        if !is_atom(root, "feed") {
+            return Err(ZoteroError::AtomError(format!(
+                "expected a feed, found <{}>",
+                root.tag_name().name()
+            )));
+        }
+
+        let mut links = PageLinks::default();
+        for link in root.children().filter(|n| is_atom(*n, "link")) {
+            let Some(url) = link
+                .attribute("href")
+                .and_then(|href| Url::parse(href).ok())
+            else {
+                continue;
+            };
+            match link.attribute("rel") {
+                Some("first") => links.first = Some(url),
+                Some("prev") => links.prev = Some(url),
+                Some("next") => links.next = Some(url),
+                Some("last") => links.last = Some(url),
+                Some("alternate") => links.alternate = Some(url),
+                _ => {}
+            }
+        }
+
+        Ok(Feed {
+            id: atom_text(root, "id").unwrap_or_default(),
+            title: atom_text(root, "title").unwrap_or_default(),
+            updated: atom_text(root, "updated"),
+            total_results: zapi_text(root, "totalResults").and_then(|n| n.parse().ok()),
+            links,
+            entries: root
+                .children()
+                .filter(|n| is_atom(*n, "entry"))
+                .map(|entry| Entry::from_node(entry, xml))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.links.next.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+    pub uri: Option<String>,
+}
+
+/// Content of an entry, depending on the `content` parameter of the request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    /// `content=json`, parsed.
+    Json(Value),
+    /// Markup such as `content=html`, `content=citation` or `content=bib`.
+    Html(String),
+    Text(String),
+}
+
+/// A single object of an Atom response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub id: String,
+    pub title: String,
+    pub author: Option<Author>,
+    pub published: Option<String>,
+    pub updated: Option<String>,
+    /// `zapi:key`.
+    pub key: Option<String>,
+    /// `zapi:version`.
+    pub version: Option<i64>,
+    /// `zapi:itemType`, for items.
+    pub item_type: Option<String>,
+    pub links: Links,
+    /// The `zapi:type` of the content, e.g. `json` or `citation`.
+    pub content_type: Option<String>,
+    pub content: Option<Content>,
+}
+
+impl Entry {
+    /// Parses a single-object response, such as an item requested with
+    /// `format=atom`.
+    pub fn from_xml(xml: &str) -> Result<Self, ZoteroError> {
+        let document = Document::parse(xml)?;
+        let root = document.root_element();
+        if !is_atom(root, "entry") {
+            return Err(ZoteroError::AtomError(format!(
+                "expected an entry, found <{}>",
+                root.tag_name().name()
+            )));
+        }
+        Entry::from_node(root, xml)
+    }
+
+    fn from_node(entry: Node, xml: &str) -> Result<Self, ZoteroError> {
+        let author = child(entry, ATOM_NS, "author").map(|author| Author {
+            name: atom_text(author, "name").unwrap_or_default(),
+            uri: atom_text(author, "uri"),
+        });
+
+        let mut links = Links::default();
+        for link in entry.children().filter(|n| is_atom(*n, "link")) {
+            let Some(href) = link.attribute("href") else {
+                continue;
+            };
+            let parsed = Link {
+                href: href.to_string(),
+                content_type: link.attribute("type").map(str::to_string),
+            };
+            match link.attribute("rel").unwrap_or("alternate") {
+                "self" => links.self_link = Some(parsed),
+                "alternate" => links.alternate = Some(parsed),
+                "up" => links.up = Some(parsed),
+                "enclosure" => links.enclosure = Some(parsed),
+                rel => {
+                    links.other.insert(rel.to_string(), parsed);
+                }
+            }
+        }
+
+        let content = child(entry, ATOM_NS, "content");
+        Ok(Entry {
+            id: atom_text(entry, "id").unwrap_or_default(),
+            title: atom_text(entry, "title").unwrap_or_default(),
+            author,
+            published: atom_text(entry, "published"),
+            updated: atom_text(entry, "updated"),
+            key: zapi_text(entry, "key"),
+            version: zapi_text(entry, "version").and_then(|v| v.parse().ok()),
+            item_type: zapi_text(entry, "itemType"),
+            links,
+            content_type: content
+                .and_then(|c| c.attribute((ZAPI_NS, "type")))
+                .map(str::to_string),
+            content: content.map(|c| parse_content(c, xml)).transpose()?,
+        })
+    }
+}
+
+fn parse_content(content: Node, xml: &str) -> Result<Content, ZoteroError> {
+    let text = || content.text().unwrap_or_default().to_string();
+    let kind = content.attribute("type").unwrap_or("text");
+    if kind.ends_with("json") || content.attribute((ZAPI_NS, "type")) == Some("json") {
+        return Ok(Content::Json(serde_json::from_str(&text())?));
+    }
+    match kind {
+        "text" => Ok(Content::Text(text())),
+        "html" => Ok(Content::Html(text())),
+        // Inline XHTML or XML, kept as markup.
+        _ => Ok(Content::Html(inner_xml(content, xml).trim().to_string())),
+    }
+}
+
+/// The source text between a node's start and end tags.
+fn inner_xml<'a>(node: Node, xml: &'a str) -> &'a str {
+    match (node.first_child(), node.last_child()) {
+        (Some(first), Some(last)) => &xml[first.range().start..last.range().end],
+        _ => "",
+    }
+}
+
+fn is_atom(node: Node, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node.tag_name().namespace() == Some(ATOM_NS)
+}
+
+fn child<'a, 'input>(
+    node: Node<'a, 'input>,
+    namespace: &str,
+    name: &str,
+) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| {
+        n.is_element() && n.tag_name().name() == name && n.tag_name().namespace() == Some(namespace)
+    })
+}
+
+fn atom_text(node: Node, name: &str) -> Option<String> {
+    child(node, ATOM_NS, name).map(|n| n.text().unwrap_or_default().trim().to_string())
+}
+
+fn zapi_text(node: Node, name: &str) -> Option<String> {
+    child(node, ZAPI_NS, name).map(|n| n.text().unwrap_or_default().trim().to_string())
+}
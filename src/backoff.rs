@@ -0,0 +1,40 @@
+use reqwest::header::HeaderMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The instant before which the server has asked for no further requests,
+/// shared by all clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Backoff {
+    until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Backoff {
+    /// Time left until requests may be sent again, if any.
+    pub fn remaining(&self) -> Option<Duration> {
+        let until = (*self.until.lock().unwrap_or_else(|e| e.into_inner()))?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Records the `Backoff` header of a response, keeping the later of the
+    /// new and any earlier deadline.
+    pub fn record(&self, headers: &HeaderMap) {
+        let Some(seconds) = headers
+            .get("backoff")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<f64>().ok())
+        else {
+            return;
+        };
+        let Ok(delay) = Duration::try_from_secs_f64(seconds) else {
+            return;
+        };
+        let deadline = Instant::now() + delay;
+        let mut until = self.until.lock().unwrap_or_else(|e| e.into_inner());
+        if until.is_none_or(|until| until < deadline) {
+            *until = Some(deadline);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use serde_json::Value;
+
+use crate::query::Query;
+
+/// A bibliography as returned by `format=bib`: an XHTML `csl-bib-body`
+/// with one `csl-entry` per item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bibliography {
+    pub html: String,
+    /// HTML of each `csl-entry`, in bibliography order.
+    pub entries: Vec<String>,
+}
+
+impl Bibliography {
+    pub fn from_html(html: &str) -> Self {
+        Bibliography {
+            html: html.to_string(),
+            entries: csl_entries(html),
+        }
+    }
+
+    /// Plain-text rendering of each entry.
+    pub fn entries_text(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| html_to_text(entry))
+            .collect()
+    }
+
+    /// Plain-text rendering of the bibliography, one entry per line.
+    pub fn text(&self) -> String {
+        self.entries_text().join("\n")
+    }
+}
+
+/// The formatted citation and bibliography entry of an item, as embedded in
+/// JSON responses by `include=citation,bib`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedItem {
+    pub key: String,
+    /// In-text citation, e.g. `<span>(Smith 2014)</span>`.
+    pub citation: Option<String>,
+    /// Bibliography entry, a `csl-bib-body` with a single `csl-entry`.
+    pub bib: Option<String>,
+}
+
+impl FormattedItem {
+    pub fn from_value(item: &Value) -> Self {
+        let field = |name: &str| item[name].as_str().map(str::to_string);
+        FormattedItem {
+            key: field("key").unwrap_or_default(),
+            citation: field("citation"),
+            bib: field("bib"),
+        }
+    }
+
+    pub fn citation_text(&self) -> Option<String> {
+        self.citation.as_deref().map(html_to_text)
+    }
+
+    pub fn bib_text(&self) -> Option<String> {
+        self.bib.as_deref().map(html_to_text)
+    }
+}
+
+/// Query for formatted citations in `style`, optionally in another locale.
+pub(crate) fn citation_query(style: &str, locale: Option<&str>, params: Option<&Query>) -> Query {
+    let query = params.cloned().unwrap_or_default().style(style);
+    match locale {
+        Some(locale) => query.locale(locale),
+        None => query,
+    }
+}
+
+/// Renders formatted citation HTML as plain text.
+///
+/// Tags are dropped, with block elements such as the `csl-left-margin` of
+/// numbered styles separated by a space, entities are decoded and runs of
+/// whitespace are collapsed. Wrapped links keep their URL as text.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        if matches!(
+            tag_name(tag).to_ascii_lowercase().as_str(),
+            "br" | "div" | "p" | "li"
+        ) {
+            text.push(' ');
+        }
+        rest = &rest[open + close + 1..];
+    }
+    text.push_str(rest);
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tag_name(tag: &str) -> &str {
+    tag.trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Splits a `csl-bib-body` into its `csl-entry` divs, which may contain
+/// nested divs in numbered styles.
+fn csl_entries(html: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut entry: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    while let Some(open) = html[offset..].find('<') {
+        let start = offset + open;
+        let Some(close) = html[start..].find('>') else {
+            break;
+        };
+        let end = start + close + 1;
+        let tag = &html[start + 1..end - 1];
+        offset = end;
+        if tag_name(tag) != "div" || tag.ends_with('/') {
+            continue;
+        }
+        entry = match (entry, tag.starts_with('/')) {
+            (None, false) if tag.contains("csl-entry") => Some((start, 1)),
+            (None, _) => None,
+            (Some((entry_start, 1)), true) => {
+                entries.push(html[entry_start..end].to_string());
+                None
+            }
+            (Some((entry_start, depth)), true) => Some((entry_start, depth - 1)),
+            (Some((entry_start, depth)), false) => Some((entry_start, depth + 1)),
+        };
+    }
+    entries
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '\u{2013}',
+        "mdash" => '\u{2014}',
+        "hellip" => '\u{2026}',
+        "lsquo" => '\u{2018}',
+        "rsquo" => '\u{2019}',
+        "ldquo" => '\u{201c}',
+        "rdquo" => '\u{201d}',
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            return char::from_u32(code);
+        }
+    };
+    Some(c)
+}
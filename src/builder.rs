@@ -0,0 +1,250 @@
+use reqwest::header::HeaderValue;
+use reqwest::{Proxy, Url};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::errors::ZoteroError;
+use crate::library::Library;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::schema::SchemaCache;
+use crate::VERSION;
+
+const DEFAULT_ENDPOINT: &str = "https://api.zotero.org";
+const DEFAULT_LOCALE: &str = "en-US";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builds a [`Zotero`](crate::Zotero) or [`ZoteroAsync`](crate::ZoteroAsync) client.
+///
+/// ```
+/// use std::time::Duration;
+/// use zotero_rs::{RetryPolicy, ZoteroBuilder};
+///
+/// let zot = ZoteroBuilder::new("api-key")
+///     .group_library(123456)
+///     .timeout(Duration::from_secs(60))
+///     .user_agent_suffix("my-tool/0.1")
+///     .retry_policy(RetryPolicy::new().max_attempts(3))
+///     .no_locale()
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZoteroBuilder {
+    api_key: Option<String>,
+    library: Option<Library>,
+    endpoint: String,
+    locale: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    user_agent_suffix: Option<String>,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    schema_cache: Option<PathBuf>,
+    client: Option<reqwest::Client>,
+    blocking_client: Option<reqwest::blocking::Client>,
+}
+
+/// Validated settings shared by both clients.
+pub(crate) struct ClientConfig {
+    pub api_key: Option<String>,
+    pub endpoint: String,
+    pub library: Library,
+    pub locale: Option<String>,
+    pub user_agent: String,
+    pub retry: RetryPolicy,
+    pub rate_limiter: Option<RateLimiter>,
+    pub schema_cache: Option<PathBuf>,
+}
+
+impl ZoteroBuilder {
+    /// Starts building a client authenticated with `api_key`. An empty key
+    /// builds a client without one, which can only read public libraries.
+    pub fn new(api_key: &str) -> Self {
+        let api_key = api_key.trim();
+        ZoteroBuilder {
+            api_key: (!api_key.is_empty()).then(|| api_key.to_string()),
+            library: None,
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            locale: Some(DEFAULT_LOCALE.to_string()),
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: None,
+            proxy: None,
+            user_agent_suffix: None,
+            retry: RetryPolicy::default(),
+            rate_limiter: None,
+            schema_cache: SchemaCache::default_path(),
+            client: None,
+            blocking_client: None,
+        }
+    }
+
+    pub fn library(mut self, library: Library) -> Self {
+        self.library = Some(library);
+        self
+    }
+
+    pub fn user_library(self, user_id: u64) -> Self {
+        self.library(Library::User(user_id))
+    }
+
+    pub fn group_library(self, group_id: u64) -> Self {
+        self.library(Library::Group(group_id))
+    }
+
+    /// Base URL of the API, `https://api.zotero.org` by default.
+    pub fn endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// Locale of localized responses such as item type names, `en-US` by default.
+    pub fn locale(mut self, locale: &str) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Sends no `locale` parameter, leaving the choice to the server.
+    pub fn no_locale(mut self) -> Self {
+        self.locale = None;
+        self
+    }
+
+    /// Total time allowed for each request, 30 seconds by default. Ignored
+    /// when a custom HTTP client is given.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Removes the request timeout.
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Time allowed to establish a connection. Ignored when a custom HTTP
+    /// client is given.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes requests through `proxy`. Ignored when a custom HTTP client is given.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Text appended to the `User-Agent` header, such as `my-tool/1.0`.
+    pub fn user_agent_suffix(mut self, suffix: &str) -> Self {
+        self.user_agent_suffix = Some(suffix.to_string());
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// File in which the schema fetched by `get_schema` is cached, by default
+    /// `zotero-rs/schema.json` in the user's cache directory. If the file
+    /// cannot be written, the schema is only kept in memory.
+    pub fn schema_cache(mut self, path: &Path) -> Self {
+        self.schema_cache = Some(path.to_path_buf());
+        self
+    }
+
+    /// Keeps the schema in memory only.
+    pub fn no_schema_cache(mut self) -> Self {
+        self.schema_cache = None;
+        self
+    }
+
+    /// HTTP client used by [`build_async`](Self::build_async).
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// HTTP client used by [`build`](Self::build).
+    pub fn blocking_http_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.blocking_client = Some(client);
+        self
+    }
+
+    /// Builds a blocking client.
+    pub fn build(self) -> Result<crate::Zotero, ZoteroError> {
+        let client = match self.blocking_client {
+            Some(ref client) => client.clone(),
+            None => {
+                let mut builder = reqwest::blocking::Client::builder().timeout(self.timeout);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(ref proxy) = self.proxy {
+                    builder = builder.proxy(proxy.clone());
+                }
+                builder.build()?
+            }
+        };
+        Ok(crate::Zotero::from_config(client, self.config()?))
+    }
+
+    /// Builds an async client.
+    pub fn build_async(self) -> Result<crate::ZoteroAsync, ZoteroError> {
+        let client = match self.client {
+            Some(ref client) => client.clone(),
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(ref proxy) = self.proxy {
+                    builder = builder.proxy(proxy.clone());
+                }
+                builder.build()?
+            }
+        };
+        Ok(crate::ZoteroAsync::from_config(client, self.config()?))
+    }
+
+    fn config(self) -> Result<ClientConfig, ZoteroError> {
+        let invalid = |message: String| Err(ZoteroError::ConfigError(message));
+        let Some(library) = self.library else {
+            return invalid("no library was given".to_string());
+        };
+        match Url::parse(&self.endpoint) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+            _ => return invalid(format!("invalid endpoint {:?}", self.endpoint)),
+        }
+        if self.locale.as_deref().is_some_and(|l| l.trim().is_empty()) {
+            return invalid("locale is empty".to_string());
+        }
+        let mut user_agent = format!("zotero-rust/{}", VERSION);
+        if let Some(suffix) = self.user_agent_suffix {
+            user_agent = format!("{} {}", user_agent, suffix.trim());
+        }
+        HeaderValue::from_str(&user_agent)?;
+
+        Ok(ClientConfig {
+            api_key: self.api_key,
+            endpoint: self.endpoint.trim_end_matches('/').to_string(),
+            library,
+            locale: self.locale,
+            user_agent,
+            retry: self.retry,
+            rate_limiter: self.rate_limiter,
+            schema_cache: self.schema_cache,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::errors::ZoteroError;
+
+/// An attachment file being downloaded.
+pub struct FileDownload<R> {
+    pub reader: R,
+    /// Offset in the file of the first byte of `reader`. This is 0 if the
+    /// server ignored the requested range and is sending the whole file.
+    pub start: u64,
+    /// Length of the data that will be read, if the server reported it.
+    pub content_length: Option<u64>,
+}
+
+/// The `md5` and `mtime` an attachment item records for its file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ExpectedFile {
+    pub md5: Option<String>,
+    pub mtime: Option<i64>,
+}
+
+impl ExpectedFile {
+    pub fn from_item(item: &Value) -> Self {
+        ExpectedFile {
+            md5: item["data"]["md5"].as_str().map(str::to_string),
+            mtime: item["data"]["mtime"].as_i64(),
+        }
+    }
+}
+
+/// Path the file is downloaded to before it has been verified.
+pub(crate) fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Size of a previously interrupted download, or 0 if there is none.
+pub(crate) fn resume_offset(part: &Path) -> u64 {
+    fs::metadata(part).map(|m| m.len()).unwrap_or(0)
+}
+
+pub(crate) fn file_md5(path: &Path) -> Result<String, ZoteroError> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.consume(&buffer[..read]);
+    }
+    Ok(format!("{:x}", context.finalize()))
+}
+
+/// Verifies the downloaded `part` file, moves it to `path` and sets its
+/// modification time. Returns the size of the file.
+pub(crate) fn finish_download(
+    part: &Path,
+    path: &Path,
+    expected: &ExpectedFile,
+) -> Result<u64, ZoteroError> {
+    if let Some(ref md5) = expected.md5 {
+        let actual = file_md5(part)?;
+        if &actual != md5 {
+            fs::remove_file(part)?;
+            return Err(ZoteroError::ChecksumMismatch {
+                expected: md5.clone(),
+                actual,
+            });
+        }
+    }
+    fs::rename(part, path)?;
+    let file = File::options().write(true).open(path)?;
+    if let Some(mtime) = expected.mtime {
+        file.set_modified(UNIX_EPOCH + Duration::from_millis(mtime.max(0) as u64))?;
+    }
+    Ok(file.metadata()?.len())
+}
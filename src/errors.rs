@@ -1,6 +1,28 @@
 use reqwest;
+use reqwest::StatusCode;
+use std::fmt;
 use thiserror::Error;
-use url::ParseError;
+use url::{ParseError, Url};
+
+use crate::validate::Violation;
+
+/// An unsuccessful response from the Zotero API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub url: String,
+    pub body: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} from {}", self.status, self.url)?;
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body)?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Error)]
 pub enum ZoteroError {
@@ -16,4 +38,92 @@ pub enum ZoteroError {
     TooManyRequests(String),
     #[error("Failed to retrieve file: {0}")]
     FileRetrievalError(String),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Sync conflict: {0}")]
+    SyncConflict(String),
+    #[cfg(feature = "mirror")]
+    #[error("Mirror database error: {0}")]
+    MirrorError(#[from] rusqlite::Error),
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("File upload failed: {0}")]
+    UploadError(String),
+    #[error("Invalid client configuration: {0}")]
+    ConfigError(String),
+    #[error("Write request failed: {0}")]
+    WriteError(String),
+    #[error("ZIP archive error: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("Unsafe path in snapshot: {0}")]
+    UnsafePath(String),
+    #[error("Unsupported search condition: {0}")]
+    UnsupportedSearchCondition(String),
+    #[error("XML parse error: {0}")]
+    XmlError(#[from] roxmltree::Error),
+    #[error("Invalid Atom feed: {0}")]
+    AtomError(String),
+    #[error("Invalid item: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidItem(Vec<Violation>),
+    #[error("Unknown item type: {0}")]
+    UnknownItemType(String),
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("Not found: {0}")]
+    NotFound(ApiError),
+    #[error("Forbidden: {0}")]
+    Forbidden(ApiError),
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(ApiError),
+    #[error("Conflict: {0}")]
+    Conflict(ApiError),
+    #[error("Request entity too large: {0}")]
+    RequestEntityTooLarge(ApiError),
+    /// Any other 4xx response.
+    #[error("Client error: {0}")]
+    ClientError(ApiError),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(ApiError),
+    /// Any other 5xx response.
+    #[error("Server error: {0}")]
+    ServerError(ApiError),
+}
+
+impl ZoteroError {
+    /// Maps an unsuccessful response to the matching variant.
+    pub(crate) fn from_response(status: StatusCode, url: &Url, body: String) -> Self {
+        let error = ApiError {
+            status,
+            url: url.to_string(),
+            body,
+        };
+        match status {
+            StatusCode::NOT_FOUND => ZoteroError::NotFound(error),
+            StatusCode::FORBIDDEN => ZoteroError::Forbidden(error),
+            StatusCode::PRECONDITION_FAILED => ZoteroError::PreconditionFailed(error),
+            StatusCode::CONFLICT => ZoteroError::Conflict(error),
+            StatusCode::PAYLOAD_TOO_LARGE => ZoteroError::RequestEntityTooLarge(error),
+            StatusCode::TOO_MANY_REQUESTS => ZoteroError::TooManyRequests(error.to_string()),
+            StatusCode::SERVICE_UNAVAILABLE => ZoteroError::ServiceUnavailable(error),
+            status if status.is_server_error() => ZoteroError::ServerError(error),
+            _ => ZoteroError::ClientError(error),
+        }
+    }
+
+    /// The details of the response, if this error was caused by one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            ZoteroError::NotFound(error)
+            | ZoteroError::Forbidden(error)
+            | ZoteroError::PreconditionFailed(error)
+            | ZoteroError::Conflict(error)
+            | ZoteroError::RequestEntityTooLarge(error)
+            | ZoteroError::ClientError(error)
+            | ZoteroError::ServiceUnavailable(error)
+            | ZoteroError::ServerError(error) => Some(error),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use serde_json::{json, Value};
+
+use crate::errors::ZoteroError;
+use crate::query::{Format, Query};
+
+/// Export formats return at most 100 items per request.
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Whether a response with this `Content-Type` is parsed as JSON, which
+/// includes CSL-JSON (`application/vnd.citationstyles.csl+json`).
+pub(crate) fn is_json(content_type: &str) -> bool {
+    let mime = mime_type(content_type);
+    mime == "application/json" || mime.ends_with("+json")
+}
+
+/// Whether a response with this `Content-Type` is returned as text, such as
+/// HTML, BibTeX (`application/x-bibtex`), RIS or MODS (`application/mods+xml`).
+pub(crate) fn is_text(content_type: &str) -> bool {
+    let mime = mime_type(content_type);
+    mime.starts_with("text/")
+        || mime.starts_with("application/x-")
+        || mime == "application/xml"
+        || mime.ends_with("+xml")
+}
+
+fn mime_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+/// Query for an export, defaulting to the largest page size the API allows.
+pub(crate) fn export_query(format: Format, params: Option<&Query>) -> Result<Query, ZoteroError> {
+    if !format.is_export() {
+        return Err(ZoteroError::ConfigError(format!(
+            "{} is not an export format",
+            format.as_str()
+        )));
+    }
+    let query = params.cloned().unwrap_or_default().format(format);
+    match query.get("limit") {
+        Some(_) => Ok(query),
+        None => Ok(query.limit(EXPORT_PAGE_SIZE)),
+    }
+}
+
+/// Combines the pages of an export into a single document.
+pub(crate) fn merge_export(format: Format, pages: Vec<Value>) -> Result<String, ZoteroError> {
+    if format == Format::CslJson {
+        return Ok(merge_csljson(pages).to_string());
+    }
+    let pages = pages
+        .into_iter()
+        .map(|page| match page {
+            Value::String(text) => Ok(text),
+            other => Err(ZoteroError::UnsupportedContentType(format!(
+                "expected {} text, got {}",
+                format.as_str(),
+                other
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match format {
+        Format::Csv => merge_csv(pages),
+        Format::Mods | Format::Tei => merge_xml(pages),
+        _ => join_pages(pages),
+    })
+}
+
+/// Combines CSL-JSON pages into a single `{"items": [...]}` document.
+pub(crate) fn merge_csljson(pages: Vec<Value>) -> Value {
+    let mut items = Vec::new();
+    for page in pages {
+        match page {
+            Value::Object(mut page) => {
+                if let Some(Value::Array(mut page_items)) = page.remove("items") {
+                    items.append(&mut page_items);
+                }
+            }
+            item => items.push(item),
+        }
+    }
+    json!({ "items": items })
+}
+
+fn join_pages(pages: Vec<String>) -> String {
+    let mut document = String::new();
+    for page in pages {
+        if !document.is_empty() && !document.ends_with('\n') {
+            document.push('\n');
+        }
+        document.push_str(&page);
+    }
+    document
+}
+
+/// Every CSV page starts with the header row, which is kept only once.
+fn merge_csv(pages: Vec<String>) -> String {
+    let mut pages = pages.into_iter();
+    let first = pages.next().unwrap_or_default();
+    let rest = pages.map(|page| {
+        let page = page.trim_start_matches('\u{feff}');
+        match page.split_once('\n') {
+            Some((_, rows)) => rows.to_string(),
+            None => String::new(),
+        }
+    });
+    join_pages(std::iter::once(first).chain(rest).collect())
+}
+
+/// Every MODS or TEI page is a complete document, so the children of the
+/// later root elements are moved into the first one.
+fn merge_xml(pages: Vec<String>) -> String {
+    let mut pages = pages.into_iter();
+    let mut document = pages.next().unwrap_or_default();
+    let Some((_, mut end)) = root_content(&document) else {
+        return document;
+    };
+    for page in pages {
+        if let Some((start, stop)) = root_content(&page) {
+            let content = &page[start..stop];
+            document.insert_str(end, content);
+            end += content.len();
+        }
+    }
+    document
+}
+
+/// Byte range between the root element's start and end tags.
+fn root_content(document: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let start = loop {
+        let tag = offset + document[offset..].find('<')?;
+        match document[tag + 1..].chars().next()? {
+            '?' | '!' => offset = tag + 1,
+            _ => break tag,
+        }
+    };
+    let content_start = start + document[start..].find('>')? + 1;
+    if document[..content_start].ends_with("/>") {
+        return None;
+    }
+    let content_end = document.rfind("</")?;
+    (content_end >= content_start).then_some((content_start, content_end))
+}
@@ -1,11 +1,51 @@
 mod asynchronous;
+mod backoff;
+mod builder;
+mod export;
 mod synchronous;
 
+pub mod access;
+pub mod atom;
+pub mod bibliography;
+pub mod download;
 pub mod errors;
+pub mod library;
+#[cfg(feature = "mirror")]
+pub mod mirror;
+pub mod models;
+pub mod pagination;
+pub mod query;
+pub mod rate_limit;
+pub mod retry;
+pub mod schema;
+pub mod search;
+pub mod snapshot;
+pub mod sync;
+pub mod upload;
+pub mod validate;
+pub mod write;
 pub use errors::ZoteroError as Error;
 
+pub use access::{AccessibleLibrary, Permissions};
 pub use asynchronous::Zotero as ZoteroAsync;
+pub use bibliography::{Bibliography, FormattedItem};
+pub use builder::ZoteroBuilder;
+pub use library::Library;
+pub use models::{
+    Collection, Creator, Group, Item, ItemData, Links, Relations, SavedSearch, SearchCondition,
+    SearchOperator, Tag,
+};
+pub use pagination::{Listing, Page};
+pub use query::Query;
+pub use rate_limit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use schema::Schema;
+pub use snapshot::{AttachmentFile, Snapshot};
+pub use sync::{ObjectKind, SyncResult, SyncStore};
 pub use synchronous::Zotero;
+pub use upload::{AttachmentUpload, FileInfo, PatchAlgorithm, UploadOutcome};
+pub use validate::{ItemTypeSchema, Violation};
+pub use write::{WriteFailure, WriteResponse};
 
 const VERSION: &str = "1";
 const API_VERSION: &str = "3";
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::ZoteroError;
+
+/// A user or group library, identified by its numeric ID.
+///
+/// Displays and parses as the URL prefix of the library's endpoints:
+///
+/// ```
+/// use zotero_rs::Library;
+///
+/// let library: Library = "groups/123456".parse().unwrap();
+/// assert_eq!(library, Library::Group(123456));
+/// assert_eq!(Library::User(42).to_string(), "users/42");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Library {
+    User(u64),
+    Group(u64),
+}
+
+impl Library {
+    /// Builds a library from a type (`user`/`users` or `group`/`groups`)
+    /// and an ID, as found in API responses and configuration.
+    pub fn from_parts(library_type: &str, id: &str) -> Result<Self, ZoteroError> {
+        let id = id
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| ZoteroError::ConfigError(format!("invalid library ID {:?}", id)))?;
+        match library_type {
+            "user" | "users" => Ok(Library::User(id)),
+            "group" | "groups" => Ok(Library::Group(id)),
+            _ => Err(ZoteroError::ConfigError(format!(
+                "unknown library type {:?}",
+                library_type
+            ))),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        match self {
+            Library::User(id) | Library::Group(id) => *id,
+        }
+    }
+
+    /// The path segment of the library type, `users` or `groups`.
+    pub fn type_path(&self) -> &'static str {
+        match self {
+            Library::User(_) => "users",
+            Library::Group(_) => "groups",
+        }
+    }
+}
+
+impl fmt::Display for Library {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.type_path(), self.id())
+    }
+}
+
+impl FromStr for Library {
+    type Err = ZoteroError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (library_type, id) = s
+            .split_once('/')
+            .ok_or_else(|| ZoteroError::ConfigError(format!("invalid library {:?}", s)))?;
+        Self::from_parts(library_type, id)
+    }
+}
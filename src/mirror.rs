@@ -0,0 +1,386 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::errors::ZoteroError;
+use crate::library::Library;
+use crate::sync::{ObjectKind, SyncStore};
+use crate::Zotero;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS libraries (
+    library TEXT PRIMARY KEY,
+    version INTEGER NOT NULL DEFAULT 0,
+    fulltext_version INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS objects (
+    library TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    json TEXT NOT NULL,
+    PRIMARY KEY (library, kind, key)
+);
+CREATE TABLE IF NOT EXISTS tags (
+    library TEXT NOT NULL,
+    item_key TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    type INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (library, item_key, tag)
+);
+CREATE TABLE IF NOT EXISTS collection_items (
+    library TEXT NOT NULL,
+    collection_key TEXT NOT NULL,
+    item_key TEXT NOT NULL,
+    PRIMARY KEY (library, collection_key, item_key)
+);
+CREATE TABLE IF NOT EXISTS relations (
+    library TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    predicate TEXT NOT NULL,
+    object TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS relations_subject ON relations (library, kind, key);
+CREATE TABLE IF NOT EXISTS fulltext (
+    library TEXT NOT NULL,
+    item_key TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    json TEXT NOT NULL,
+    PRIMARY KEY (library, item_key)
+);
+";
+
+/// Local SQLite copy of a library, kept up to date with [`Mirror::sync`].
+///
+/// A single database can hold several libraries, stored under their
+/// [`Library`] prefix, e.g. `users/12345` or `groups/67890`.
+pub struct Mirror {
+    conn: Connection,
+    library: String,
+}
+
+impl Mirror {
+    pub fn open<P: AsRef<Path>>(path: P, library: Library) -> Result<Self, ZoteroError> {
+        Self::with_connection(Connection::open(path)?, library)
+    }
+
+    pub fn open_in_memory(library: Library) -> Result<Self, ZoteroError> {
+        Self::with_connection(Connection::open_in_memory()?, library)
+    }
+
+    fn with_connection(conn: Connection, library: Library) -> Result<Self, ZoteroError> {
+        conn.execute_batch(SCHEMA)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO libraries (library) VALUES (?1)",
+            params![library.to_string()],
+        )?;
+        Ok(Mirror {
+            conn,
+            library: library.to_string(),
+        })
+    }
+
+    /// Brings the mirror up to date with its library, including full-text
+    /// content. Returns the new library version.
+    ///
+    /// `zotero` must point at the library the mirror was opened for.
+    pub fn sync(&mut self, zotero: &Zotero) -> Result<i64, ZoteroError> {
+        if zotero.library().to_string() != self.library {
+            return Err(ZoteroError::ConfigError(format!(
+                "mirror of {} cannot be synced from {}",
+                self.library,
+                zotero.library()
+            )));
+        }
+        let version = zotero.sync_store(self)?;
+        self.sync_fulltext(zotero)?;
+        Ok(version)
+    }
+
+    fn sync_fulltext(&mut self, zotero: &Zotero) -> Result<(), ZoteroError> {
+        let since = self.fulltext_version()?;
+        let changed = zotero.get_new_fulltext(&since.to_string(), None)?;
+        let mut newest = since;
+        if let Some(changed) = changed.as_object() {
+            for (item_key, version) in changed {
+                let version = version.as_i64().unwrap_or(0);
+                let content = zotero.get_fulltext_item(item_key, None)?;
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO fulltext (library, item_key, version, content, json)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        self.library,
+                        item_key,
+                        version,
+                        content["content"].as_str().unwrap_or(""),
+                        content.to_string()
+                    ],
+                )?;
+                newest = newest.max(version);
+            }
+        }
+        self.conn.execute(
+            "UPDATE libraries SET fulltext_version = ?2 WHERE library = ?1",
+            params![self.library, newest],
+        )?;
+        Ok(())
+    }
+
+    fn fulltext_version(&self) -> Result<i64, ZoteroError> {
+        Ok(self.conn.query_row(
+            "SELECT fulltext_version FROM libraries WHERE library = ?1",
+            params![self.library],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn get(&self, kind: ObjectKind, key: &str) -> Result<Option<Value>, ZoteroError> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT json FROM objects WHERE library = ?1 AND kind = ?2 AND key = ?3",
+                params![self.library, kind.path(), key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    pub fn all(&self, kind: ObjectKind) -> Result<Vec<Value>, ZoteroError> {
+        self.query_json(
+            "SELECT json FROM objects WHERE library = ?1 AND kind = ?2 ORDER BY key",
+            params![self.library, kind.path()],
+        )
+    }
+
+    pub fn item(&self, key: &str) -> Result<Option<Value>, ZoteroError> {
+        self.get(ObjectKind::Item, key)
+    }
+
+    pub fn items(&self) -> Result<Vec<Value>, ZoteroError> {
+        self.all(ObjectKind::Item)
+    }
+
+    pub fn collection(&self, key: &str) -> Result<Option<Value>, ZoteroError> {
+        self.get(ObjectKind::Collection, key)
+    }
+
+    pub fn collections(&self) -> Result<Vec<Value>, ZoteroError> {
+        self.all(ObjectKind::Collection)
+    }
+
+    pub fn collection_items(&self, collection_key: &str) -> Result<Vec<Value>, ZoteroError> {
+        self.query_json(
+            "SELECT o.json FROM objects o
+             JOIN collection_items c ON c.library = o.library AND c.item_key = o.key
+             WHERE o.library = ?1 AND o.kind = 'items' AND c.collection_key = ?2
+             ORDER BY o.key",
+            params![self.library, collection_key],
+        )
+    }
+
+    pub fn items_with_tag(&self, tag: &str) -> Result<Vec<Value>, ZoteroError> {
+        self.query_json(
+            "SELECT o.json FROM objects o
+             JOIN tags t ON t.library = o.library AND t.item_key = o.key
+             WHERE o.library = ?1 AND o.kind = 'items' AND t.tag = ?2
+             ORDER BY o.key",
+            params![self.library, tag],
+        )
+    }
+
+    /// Returns every tag in the library with the number of items it is attached to.
+    pub fn tags(&self) -> Result<Vec<(String, i64)>, ZoteroError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag, COUNT(*) FROM tags WHERE library = ?1 GROUP BY tag ORDER BY tag",
+        )?;
+        let rows = stmt.query_map(params![self.library], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Returns the `(predicate, object)` relations of an object.
+    pub fn relations(
+        &self,
+        kind: ObjectKind,
+        key: &str,
+    ) -> Result<Vec<(String, String)>, ZoteroError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT predicate, object FROM relations
+             WHERE library = ?1 AND kind = ?2 AND key = ?3 ORDER BY predicate, object",
+        )?;
+        let rows = stmt.query_map(params![self.library, kind.path(), key], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn fulltext(&self, item_key: &str) -> Result<Option<String>, ZoteroError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT content FROM fulltext WHERE library = ?1 AND item_key = ?2",
+                params![self.library, item_key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn query_json<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> Result<Vec<Value>, ZoteroError> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+        let mut values = Vec::new();
+        for json in rows {
+            values.push(serde_json::from_str(&json?)?);
+        }
+        Ok(values)
+    }
+
+    fn remove(&self, kind: ObjectKind, key: &str) -> Result<(), ZoteroError> {
+        let library = &self.library;
+        self.conn.execute(
+            "DELETE FROM objects WHERE library = ?1 AND kind = ?2 AND key = ?3",
+            params![library, kind.path(), key],
+        )?;
+        self.conn.execute(
+            "DELETE FROM relations WHERE library = ?1 AND kind = ?2 AND key = ?3",
+            params![library, kind.path(), key],
+        )?;
+        match kind {
+            ObjectKind::Item => {
+                self.conn.execute(
+                    "DELETE FROM tags WHERE library = ?1 AND item_key = ?2",
+                    params![library, key],
+                )?;
+                self.conn.execute(
+                    "DELETE FROM collection_items WHERE library = ?1 AND item_key = ?2",
+                    params![library, key],
+                )?;
+            }
+            // Memberships of a collection come from its items, so they are
+            // kept when the collection itself changes.
+            ObjectKind::Collection | ObjectKind::Search => {}
+        }
+        Ok(())
+    }
+
+    fn insert(&self, kind: ObjectKind, object: &Value) -> Result<(), ZoteroError> {
+        let key = match object["key"].as_str() {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        let library = &self.library;
+        self.conn.execute(
+            "INSERT INTO objects (library, kind, key, version, json) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                library,
+                kind.path(),
+                key,
+                object["version"].as_i64().unwrap_or(0),
+                object.to_string()
+            ],
+        )?;
+        let data = &object["data"];
+        if let Some(relations) = data["relations"].as_object() {
+            for (predicate, targets) in relations {
+                let targets = match targets {
+                    Value::Array(targets) => targets.iter().filter_map(Value::as_str).collect(),
+                    target => target.as_str().into_iter().collect::<Vec<_>>(),
+                };
+                for target in targets {
+                    self.conn.execute(
+                        "INSERT INTO relations (library, kind, key, predicate, object)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![library, kind.path(), key, predicate, target],
+                    )?;
+                }
+            }
+        }
+        if kind == ObjectKind::Item {
+            for tag in data["tags"].as_array().into_iter().flatten() {
+                if let Some(name) = tag["tag"].as_str() {
+                    self.conn.execute(
+                        "INSERT OR REPLACE INTO tags (library, item_key, tag, type)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![library, key, name, tag["type"].as_i64().unwrap_or(0)],
+                    )?;
+                }
+            }
+            for collection in data["collections"].as_array().into_iter().flatten() {
+                if let Some(collection) = collection.as_str() {
+                    self.conn.execute(
+                        "INSERT OR IGNORE INTO collection_items (library, collection_key, item_key)
+                         VALUES (?1, ?2, ?3)",
+                        params![library, collection, key],
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SyncStore for Mirror {
+    fn library_version(&self) -> Result<i64, ZoteroError> {
+        let version = self
+            .conn
+            .query_row(
+                "SELECT version FROM libraries WHERE library = ?1",
+                params![self.library],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(version.unwrap_or(0))
+    }
+
+    fn upsert(&mut self, kind: ObjectKind, objects: &[Value]) -> Result<(), ZoteroError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for object in objects {
+            if let Some(key) = object["key"].as_str() {
+                self.remove(kind, key)?;
+            }
+            self.insert(kind, object)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, kind: ObjectKind, keys: &[String]) -> Result<(), ZoteroError> {
+        let tx = self.conn.unchecked_transaction()?;
+        for key in keys {
+            self.remove(kind, key)?;
+            match kind {
+                ObjectKind::Item => {
+                    self.conn.execute(
+                        "DELETE FROM fulltext WHERE library = ?1 AND item_key = ?2",
+                        params![self.library, key],
+                    )?;
+                }
+                ObjectKind::Collection => {
+                    self.conn.execute(
+                        "DELETE FROM collection_items WHERE library = ?1 AND collection_key = ?2",
+                        params![self.library, key],
+                    )?;
+                }
+                ObjectKind::Search => {}
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn set_library_version(&mut self, version: i64) -> Result<(), ZoteroError> {
+        self.conn.execute(
+            "UPDATE libraries SET version = ?2 WHERE library = ?1",
+            params![self.library, version],
+        )?;
+        Ok(())
+    }
+}
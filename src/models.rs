@@ -0,0 +1,353 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// A single link in the `links` object of an API response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub href: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Links {
+    #[serde(rename = "self", default, skip_serializing_if = "Option::is_none")]
+    pub self_link: Option<Link>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alternate: Option<Link>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub up: Option<Link>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enclosure: Option<Link>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Link>,
+}
+
+/// The library an object belongs to, as embedded in API responses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryInfo {
+    #[serde(rename = "type")]
+    pub library_type: String,
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub links: Links,
+}
+
+/// Target of a relation predicate, which the API sends as a single URI or a list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RelationTarget {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Relations of an item or collection, keyed by predicate (e.g. `dc:replaces`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Relations(pub BTreeMap<String, RelationTarget>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Creator {
+    pub creator_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    /// Single-field name, used instead of `first_name`/`last_name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A tag as attached to an item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemTag {
+    pub tag: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub tag_type: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    pub item_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub creators: Vec<Creator>,
+    #[serde(default)]
+    pub tags: Vec<ItemTag>,
+    #[serde(default)]
+    pub collections: Vec<String>,
+    #[serde(default)]
+    pub relations: Relations,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_item: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_added: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_modified: Option<String>,
+    /// Remaining item-type specific fields (`publisher`, `DOI`, `extra`, ...).
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl ItemData {
+    /// Returns a field that is not modelled explicitly, such as `publisher`.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.other.get(name).and_then(Value::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub key: String,
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<LibraryInfo>,
+    #[serde(default)]
+    pub links: Links,
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+    pub data: ItemData,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    pub name: String,
+    /// Parent collection key; the API sends `false` for top-level collections.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_parent",
+        serialize_with = "serialize_parent"
+    )]
+    pub parent_collection: Option<String>,
+    #[serde(default)]
+    pub relations: Relations,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collection {
+    pub key: String,
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<LibraryInfo>,
+    #[serde(default)]
+    pub links: Links,
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+    pub data: CollectionData,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMeta {
+    #[serde(rename = "type", default)]
+    pub tag_type: u8,
+    #[serde(default)]
+    pub num_items: u64,
+}
+
+/// A tag as returned by the `/tags` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub tag: String,
+    #[serde(default)]
+    pub links: Links,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<TagMeta>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Comparison made by a [`SearchCondition`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum SearchOperator {
+    Is,
+    IsNot,
+    BeginsWith,
+    Contains,
+    DoesNotContain,
+    IsLessThan,
+    IsGreaterThan,
+    IsBefore,
+    IsAfter,
+    IsInTheLast,
+    /// An operator this library does not know about.
+    Other(String),
+}
+
+impl SearchOperator {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SearchOperator::Is => "is",
+            SearchOperator::IsNot => "isNot",
+            SearchOperator::BeginsWith => "beginsWith",
+            SearchOperator::Contains => "contains",
+            SearchOperator::DoesNotContain => "doesNotContain",
+            SearchOperator::IsLessThan => "isLessThan",
+            SearchOperator::IsGreaterThan => "isGreaterThan",
+            SearchOperator::IsBefore => "isBefore",
+            SearchOperator::IsAfter => "isAfter",
+            SearchOperator::IsInTheLast => "isInTheLast",
+            SearchOperator::Other(operator) => operator,
+        }
+    }
+}
+
+impl From<String> for SearchOperator {
+    fn from(operator: String) -> Self {
+        match operator.as_str() {
+            "is" => SearchOperator::Is,
+            "isNot" => SearchOperator::IsNot,
+            "beginsWith" => SearchOperator::BeginsWith,
+            "contains" => SearchOperator::Contains,
+            "doesNotContain" => SearchOperator::DoesNotContain,
+            "isLessThan" => SearchOperator::IsLessThan,
+            "isGreaterThan" => SearchOperator::IsGreaterThan,
+            "isBefore" => SearchOperator::IsBefore,
+            "isAfter" => SearchOperator::IsAfter,
+            "isInTheLast" => SearchOperator::IsInTheLast,
+            _ => SearchOperator::Other(operator),
+        }
+    }
+}
+
+impl From<SearchOperator> for String {
+    fn from(operator: SearchOperator) -> Self {
+        operator.as_str().to_string()
+    }
+}
+
+/// A single condition of a saved search, e.g. `title contains "climate"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCondition {
+    pub condition: String,
+    pub operator: SearchOperator,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl SearchCondition {
+    pub fn new(condition: &str, operator: SearchOperator, value: &str) -> Self {
+        SearchCondition {
+            condition: condition.to_string(),
+            operator,
+            value: value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearchData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub conditions: Vec<SearchCondition>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub key: String,
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<LibraryInfo>,
+    #[serde(default)]
+    pub links: Links,
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+    pub data: SavedSearchData,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupData {
+    pub id: u64,
+    #[serde(default)]
+    pub version: i64,
+    pub name: String,
+    #[serde(default)]
+    pub owner: u64,
+    /// `Private`, `PublicOpen` or `PublicClosed`.
+    #[serde(rename = "type", default)]
+    pub group_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub url: String,
+    /// Who may read the library: `all` or `members`.
+    #[serde(default)]
+    pub library_reading: String,
+    /// Who may edit the library: `members` or `admins`.
+    #[serde(default)]
+    pub library_editing: String,
+    /// Who may edit files: `none`, `members` or `admins`.
+    #[serde(default)]
+    pub file_editing: String,
+    #[serde(default)]
+    pub members: Vec<u64>,
+    #[serde(default)]
+    pub admins: Vec<u64>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A group as returned by the `/groups` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub id: u64,
+    pub version: i64,
+    #[serde(default)]
+    pub links: Links,
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+    pub data: GroupData,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+fn deserialize_parent<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(key) => Ok(Some(key)),
+        _ => Ok(None),
+    }
+}
+
+fn serialize_parent<S>(parent: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match parent {
+        Some(key) => serializer.serialize_str(key),
+        None => serializer.serialize_bool(false),
+    }
+}
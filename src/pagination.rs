@@ -0,0 +1,140 @@
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::write::last_modified_version;
+
+/// Page size used by the API when no `limit` is given.
+pub(crate) const DEFAULT_LIMIT: usize = 25;
+
+/// Largest page the API returns, whatever `limit` asks for.
+pub(crate) const MAX_LIMIT: usize = 100;
+
+/// Listing endpoints that return paginated results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Listing {
+    Items,
+    Top,
+    Trash,
+    Children(String),
+    ItemTags(String),
+    Collections,
+    CollectionsTop,
+    CollectionsSub(String),
+    CollectionItems(String),
+    Tags,
+    /// Groups of a user library.
+    Groups,
+    Searches,
+}
+
+impl Listing {
+    pub fn path(&self) -> String {
+        match self {
+            Listing::Items => "items".to_string(),
+            Listing::Top => "items/top".to_string(),
+            Listing::Trash => "items/trash".to_string(),
+            Listing::Children(key) => format!("items/{}/children", key),
+            Listing::ItemTags(key) => format!("items/{}/tags", key),
+            Listing::Collections => "collections".to_string(),
+            Listing::CollectionsTop => "collections/top".to_string(),
+            Listing::CollectionsSub(key) => format!("collections/{}/collections", key),
+            Listing::CollectionItems(key) => format!("collections/{}/items", key),
+            Listing::Tags => "tags".to_string(),
+            Listing::Groups => "groups".to_string(),
+            Listing::Searches => "searches".to_string(),
+        }
+    }
+}
+
+/// Links parsed from the `Link` response header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageLinks {
+    pub first: Option<Url>,
+    pub prev: Option<Url>,
+    pub next: Option<Url>,
+    pub last: Option<Url>,
+    pub alternate: Option<Url>,
+}
+
+/// A single response from a listing endpoint together with its pagination headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub data: Value,
+    pub total_results: Option<u64>,
+    pub last_modified_version: Option<i64>,
+    pub links: PageLinks,
+}
+
+impl Page {
+    pub(crate) fn new(data: Value, headers: &HeaderMap) -> Self {
+        let total_results = headers
+            .get("total-results")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        let links = headers
+            .get(reqwest::header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .map(parse_link_header)
+            .unwrap_or_default();
+        Page {
+            data,
+            total_results,
+            last_modified_version: last_modified_version(headers),
+            links,
+        }
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.links.next.is_some()
+    }
+
+    /// Returns the results of the page as a list.
+    pub fn into_values(self) -> Vec<Value> {
+        match self.data {
+            Value::Array(values) => values,
+            value => vec![value],
+        }
+    }
+}
+
+/// Parses a header of the form `<url>; rel="next", <url>; rel="last"`.
+///
+/// URLs are read up to their closing `>`, as they may contain unencoded
+/// commas, e.g. in `itemKey=A,B`.
+pub(crate) fn parse_link_header(header: &str) -> PageLinks {
+    let mut links = PageLinks::default();
+    let mut rest = header;
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let url = Url::parse(&rest[open + 1..open + close]);
+        rest = &rest[open + close + 1..];
+        let params = &rest[..rest.find('<').unwrap_or(rest.len())];
+        let Ok(url) = url else {
+            continue;
+        };
+        for segment in params.split(';') {
+            let Some(rel) = segment.trim().strip_prefix("rel=") else {
+                continue;
+            };
+            for rel in rel
+                .trim_end_matches(',')
+                .trim()
+                .trim_matches('"')
+                .split_whitespace()
+            {
+                match rel {
+                    "first" => links.first = Some(url.clone()),
+                    "prev" => links.prev = Some(url.clone()),
+                    "next" => links.next = Some(url.clone()),
+                    "last" => links.last = Some(url.clone()),
+                    "alternate" => links.alternate = Some(url.clone()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    links
+}
@@ -0,0 +1,317 @@
+/// Fields that listing endpoints can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    DateAdded,
+    DateModified,
+    Title,
+    Creator,
+    ItemType,
+    Date,
+    Publisher,
+    PublicationTitle,
+    JournalAbbreviation,
+    Language,
+    AccessDate,
+    LibraryCatalog,
+    CallNumber,
+    Rights,
+    AddedBy,
+    NumItems,
+}
+
+impl Sort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sort::DateAdded => "dateAdded",
+            Sort::DateModified => "dateModified",
+            Sort::Title => "title",
+            Sort::Creator => "creator",
+            Sort::ItemType => "itemType",
+            Sort::Date => "date",
+            Sort::Publisher => "publisher",
+            Sort::PublicationTitle => "publicationTitle",
+            Sort::JournalAbbreviation => "journalAbbreviation",
+            Sort::Language => "language",
+            Sort::AccessDate => "accessDate",
+            Sort::LibraryCatalog => "libraryCatalog",
+            Sort::CallNumber => "callNumber",
+            Sort::Rights => "rights",
+            Sort::AddedBy => "addedBy",
+            Sort::NumItems => "numItems",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Asc => "asc",
+            Direction::Desc => "desc",
+        }
+    }
+}
+
+/// Search mode for the `q` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QMode {
+    TitleCreatorYear,
+    Everything,
+}
+
+impl QMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QMode::TitleCreatorYear => "titleCreatorYear",
+            QMode::Everything => "everything",
+        }
+    }
+}
+
+/// Formats that can be embedded in JSON responses via `include`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Include {
+    Bib,
+    Citation,
+    Data,
+}
+
+impl Include {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Include::Bib => "bib",
+            Include::Citation => "citation",
+            Include::Data => "data",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Atom,
+    Bib,
+    Keys,
+    Versions,
+    Bibtex,
+    Biblatex,
+    Ris,
+    CslJson,
+    Csv,
+    Mods,
+    Refer,
+    Tei,
+    Wikipedia,
+    Coins,
+}
+
+impl Format {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Atom => "atom",
+            Format::Bib => "bib",
+            Format::Keys => "keys",
+            Format::Versions => "versions",
+            Format::Bibtex => "bibtex",
+            Format::Biblatex => "biblatex",
+            Format::Ris => "ris",
+            Format::CslJson => "csljson",
+            Format::Csv => "csv",
+            Format::Mods => "mods",
+            Format::Refer => "refer",
+            Format::Tei => "tei",
+            Format::Wikipedia => "wikipedia",
+            Format::Coins => "coins",
+        }
+    }
+
+    /// Whether this is one of the export formats of the items endpoints.
+    pub fn is_export(&self) -> bool {
+        !matches!(
+            self,
+            Format::Json | Format::Atom | Format::Bib | Format::Keys | Format::Versions
+        )
+    }
+}
+
+/// Query parameters accepted by the `get_*` methods.
+///
+/// ```
+/// use zotero_rs::query::{Direction, Query, Sort};
+///
+/// let query = Query::new()
+///     .limit(25)
+///     .sort(Sort::DateModified)
+///     .direction(Direction::Desc)
+///     .item_types(&["book", "journalArticle"])
+///     .not_tag("to-read");
+/// assert_eq!(query.get("itemType"), Some("book || journalArticle"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    params: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a parameter that has no typed setter, replacing any previous value.
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.set(key, value.to_string());
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.set("limit", limit.to_string());
+        self
+    }
+
+    pub fn start(mut self, start: usize) -> Self {
+        self.set("start", start.to_string());
+        self
+    }
+
+    pub fn sort(mut self, sort: Sort) -> Self {
+        self.set("sort", sort.as_str().to_string());
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.set("direction", direction.as_str().to_string());
+        self
+    }
+
+    pub fn q(mut self, q: &str) -> Self {
+        self.set("q", q.to_string());
+        self
+    }
+
+    pub fn qmode(mut self, qmode: QMode) -> Self {
+        self.set("qmode", qmode.as_str().to_string());
+        self
+    }
+
+    pub fn item_type(mut self, item_type: &str) -> Self {
+        self.set("itemType", item_type.to_string());
+        self
+    }
+
+    /// Matches items of any of the given types.
+    pub fn item_types(mut self, item_types: &[&str]) -> Self {
+        self.set("itemType", item_types.join(" || "));
+        self
+    }
+
+    /// Excludes items of the given type.
+    pub fn not_item_type(mut self, item_type: &str) -> Self {
+        self.set("itemType", format!("-{}", item_type));
+        self
+    }
+
+    /// Requires the given tag. Calling this repeatedly combines the tags with AND.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.params.push(("tag".to_string(), tag.to_string()));
+        self
+    }
+
+    /// Requires any of the given tags.
+    pub fn tag_any(mut self, tags: &[&str]) -> Self {
+        self.params.push(("tag".to_string(), tags.join(" || ")));
+        self
+    }
+
+    /// Excludes items with the given tag.
+    pub fn not_tag(mut self, tag: &str) -> Self {
+        self.params.push(("tag".to_string(), format!("-{}", tag)));
+        self
+    }
+
+    pub fn since(mut self, version: i64) -> Self {
+        self.set("since", version.to_string());
+        self
+    }
+
+    pub fn include(mut self, include: &[Include]) -> Self {
+        let values: Vec<&str> = include.iter().map(Include::as_str).collect();
+        self.set("include", values.join(","));
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.set("format", format.as_str().to_string());
+        self
+    }
+
+    /// Content of each entry of an Atom feed, e.g. `json` or `citation`.
+    pub fn content(mut self, content: &[&str]) -> Self {
+        self.set("content", content.join(","));
+        self
+    }
+
+    /// CSL style used for `format=bib` and `include=bib,citation`, e.g. `apa`.
+    pub fn style(mut self, style: &str) -> Self {
+        self.set("style", style.to_string());
+        self
+    }
+
+    /// Locale of formatted citations, overriding the client's locale.
+    pub fn locale(mut self, locale: &str) -> Self {
+        self.set("locale", locale.to_string());
+        self
+    }
+
+    /// Wraps URLs and DOIs in formatted citations in HTML links.
+    pub fn linkwrap(mut self, linkwrap: bool) -> Self {
+        self.set("linkwrap", (linkwrap as u8).to_string());
+        self
+    }
+
+    /// Restricts the results to the given item keys (up to 50).
+    pub fn item_keys(mut self, keys: &[&str]) -> Self {
+        self.set("itemKey", keys.join(","));
+        self
+    }
+
+    /// Restricts the results to the given collection keys (up to 50).
+    pub fn collection_keys(mut self, keys: &[&str]) -> Self {
+        self.set("collectionKey", keys.join(","));
+        self
+    }
+
+    /// Restricts the results to the given saved search keys (up to 50).
+    pub fn search_keys(mut self, keys: &[&str]) -> Self {
+        self.set("searchKey", keys.join(","));
+        self
+    }
+
+    /// Includes items in the trash, which listing endpoints omit by default.
+    pub fn include_trashed(mut self) -> Self {
+        self.set("includeTrashed", "1".to_string());
+        self
+    }
+
+    /// Returns the first value of `key`, if set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        self.params.retain(|(k, _)| k != key);
+        self.params.push((key.to_string(), value));
+    }
+}
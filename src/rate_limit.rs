@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A token bucket limiting how fast requests are sent.
+///
+/// Clones share the same bucket, so a limiter set on a client also limits
+/// all clones of that client, whichever thread or task they run on.
+///
+/// ```
+/// use zotero_rs::{RateLimiter, Zotero};
+///
+/// let zot = Zotero::group_lib("123456", "api-key")
+///     .unwrap()
+///     .with_rate_limiter(RateLimiter::new(5.0, 10));
+/// let worker = zot.clone(); // draws from the same 5 requests per second
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allows `requests_per_second` on average and up to `burst` requests at
+    /// once after a pause.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not positive.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            requests_per_second,
+            burst,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Takes a token and returns how long the caller must wait before sending.
+    ///
+    /// Tokens are reserved even when none are available yet, so concurrent
+    /// callers are queued instead of all waking up at once.
+    pub(crate) fn acquire(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.requests_per_second)
+        }
+    }
+}
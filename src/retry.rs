@@ -0,0 +1,158 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::write::WRITE_TOKEN;
+
+/// When and how long to wait before retrying a failed request.
+///
+/// Creating objects is retried safely thanks to a `Zotero-Write-Token`. Other
+/// POST requests, such as file uploads, are only retried on 429 and when the
+/// connection could not be made.
+///
+/// ```
+/// use std::time::Duration;
+/// use zotero_rs::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(3)
+///     .base_delay(Duration::from_millis(500))
+///     .jitter(0.0);
+/// assert_eq!(policy.delay(1, None), Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: f64,
+    statuses: Vec<StatusCode>,
+    connection_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.5,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            connection_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy that sends every request exactly once.
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Total number of times a request is sent, including the first. At least 1.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry. Each further retry doubles it.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Upper bound of the computed delay. Delays requested by the server
+    /// through `Retry-After` or `Backoff` are not capped.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Fraction of each computed delay, between 0 and 1, that is randomized
+    /// so that clients do not retry in lockstep.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Replaces the response statuses that are retried.
+    pub fn statuses(mut self, statuses: &[StatusCode]) -> Self {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Whether connection failures and timeouts are retried.
+    pub fn connection_errors(mut self, retry: bool) -> Self {
+        self.connection_errors = retry;
+        self
+    }
+
+    /// Delay before retry number `retry` (starting at 0). A delay requested
+    /// by the server takes precedence over the computed one.
+    pub fn delay(&self, retry: u32, server_delay: Option<Duration>) -> Duration {
+        if let Some(delay) = server_delay {
+            return delay;
+        }
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        if self.jitter == 0.0 {
+            return delay;
+        }
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+
+    /// Whether a request that has failed `retry + 1` times may be sent again.
+    pub(crate) fn allows_retry(&self, retry: u32) -> bool {
+        retry + 1 < self.max_attempts
+    }
+
+    /// Whether a response status is retried. A request that is not safe to
+    /// replay is only retried on 429, which the server sends before
+    /// processing it.
+    pub(crate) fn retries_status(&self, status: StatusCode, replay_safe: bool) -> bool {
+        self.statuses.contains(&status) && (replay_safe || status == StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// Whether a failed request is retried. A request that is not safe to
+    /// replay is only retried if no connection was made, as it may have been
+    /// processed before a timeout.
+    pub(crate) fn retries_error(&self, error: &reqwest::Error, replay_safe: bool) -> bool {
+        self.connection_errors && (error.is_connect() || (replay_safe && error.is_timeout()))
+    }
+}
+
+/// Whether sending a request twice has the same effect as sending it once:
+/// anything but a POST, or a POST whose `Zotero-Write-Token` lets the server
+/// discard the repeat.
+pub(crate) fn is_replay_safe(method: &Method, headers: &HeaderMap) -> bool {
+    method != Method::POST || headers.contains_key(WRITE_TOKEN)
+}
+
+/// Delay requested by the server through the `Retry-After` or `Backoff` header.
+pub(crate) fn server_delay(headers: &HeaderMap) -> Option<Duration> {
+    ["backoff", "retry-after"].iter().find_map(|name| {
+        let seconds = headers
+            .get(*name)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse::<f64>()
+            .ok()?;
+        Duration::try_from_secs_f64(seconds).ok()
+    })
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::errors::ZoteroError;
+use crate::validate::ItemTypeSchema;
+
+/// Locale used for localized names missing from the requested locale.
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// The global schema published at `/schema`: item types with their fields
+/// and creator types, field metadata, CSL mappings and localized names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    pub version: i64,
+    pub item_types: Vec<SchemaItemType>,
+    /// Field metadata, such as which fields hold dates.
+    #[serde(default)]
+    pub meta: Value,
+    /// Mappings between Zotero and CSL types, fields and creator types.
+    #[serde(default)]
+    pub csl: Value,
+    /// Localized names, keyed by locale such as `en-US`.
+    #[serde(default)]
+    pub locales: BTreeMap<String, SchemaLocale>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaItemType {
+    pub item_type: String,
+    /// Fields in display order.
+    #[serde(default)]
+    pub fields: Vec<SchemaField>,
+    /// Creator types, primary type first.
+    #[serde(default)]
+    pub creator_types: Vec<SchemaCreatorType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaField {
+    pub field: String,
+    /// The base field this type-specific field maps to, e.g. `publisher`
+    /// for `label`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_field: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCreatorType {
+    pub creator_type: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary: bool,
+}
+
+/// Localized names of a single locale.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaLocale {
+    #[serde(default)]
+    pub item_types: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub creator_types: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Schema {
+    pub fn item_type(&self, item_type: &str) -> Option<&SchemaItemType> {
+        self.item_types.iter().find(|t| t.item_type == item_type)
+    }
+
+    /// Fields and creator types allowed for `item_type`, if it exists.
+    pub fn item_type_schema(&self, item_type: &str) -> Option<ItemTypeSchema> {
+        self.item_type(item_type).map(|t| ItemTypeSchema {
+            item_type: t.item_type.clone(),
+            fields: t.fields.iter().map(|f| f.field.clone()).collect(),
+            creator_types: t
+                .creator_types
+                .iter()
+                .map(|c| c.creator_type.clone())
+                .collect(),
+        })
+    }
+
+    /// Localized name from one of the maps of [`SchemaLocale`], falling back
+    /// to `en-US` and then to the name itself.
+    fn localized<'a>(
+        &'a self,
+        locale: Option<&str>,
+        names: fn(&SchemaLocale) -> &BTreeMap<String, String>,
+        name: &'a str,
+    ) -> &'a str {
+        [locale.unwrap_or(FALLBACK_LOCALE), FALLBACK_LOCALE]
+            .iter()
+            .find_map(|locale| names(self.locales.get(*locale)?).get(name))
+            .map_or(name, String::as_str)
+    }
+
+    /// The `itemFields` response: every field of any item type, sorted by
+    /// localized name.
+    pub(crate) fn item_fields_response(&self, locale: Option<&str>) -> Value {
+        let mut fields: BTreeMap<&str, &str> = BTreeMap::new();
+        for field in self.item_types.iter().flat_map(|t| &t.fields) {
+            let localized = self.localized(locale, |l| &l.fields, &field.field);
+            fields.insert(&field.field, localized);
+        }
+        let mut fields: Vec<_> = fields.into_iter().collect();
+        fields.sort_by(|a, b| a.1.cmp(b.1));
+        fields
+            .into_iter()
+            .map(|(field, localized)| json!({ "field": field, "localized": localized }))
+            .collect()
+    }
+
+    /// The `itemTypeFields` response for `item_type`, in display order.
+    pub(crate) fn item_type_fields_response(
+        &self,
+        item_type: &str,
+        locale: Option<&str>,
+    ) -> Option<Value> {
+        let fields = self.item_type(item_type)?.fields.iter().map(|f| {
+            let localized = self.localized(locale, |l| &l.fields, &f.field);
+            json!({ "field": f.field, "localized": localized })
+        });
+        Some(fields.collect())
+    }
+}
+
+/// A schema together with the `ETag` it was served with.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedSchema {
+    pub etag: Option<String>,
+    pub schema: Arc<Schema>,
+    /// Whether the schema was fetched or revalidated during this session.
+    pub current: bool,
+}
+
+/// Contents of the cache file.
+#[derive(Deserialize)]
+struct CacheFile {
+    etag: Option<String>,
+    schema: Schema,
+}
+
+/// The schema cached in memory and, if a path is given, on disk, shared by
+/// all clones of a client.
+#[derive(Debug, Clone)]
+pub(crate) struct SchemaCache {
+    path: Option<PathBuf>,
+    cached: Arc<Mutex<Option<CachedSchema>>>,
+}
+
+impl SchemaCache {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        SchemaCache {
+            path,
+            cached: Arc::default(),
+        }
+    }
+
+    /// `zotero-rs/schema.json` in the user's cache directory, if there is one.
+    pub fn default_path() -> Option<PathBuf> {
+        let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+                Some(Path::new(&home).join(".cache"))
+            })?;
+        Some(cache_dir.join("zotero-rs").join("schema.json"))
+    }
+
+    /// The cached schema, read from disk on first use. An unreadable cache
+    /// file is treated as missing.
+    pub fn get(&self) -> Option<CachedSchema> {
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if cached.is_none() {
+            *cached = self.read().map(|file| CachedSchema {
+                etag: file.etag,
+                schema: Arc::new(file.schema),
+                current: false,
+            });
+        }
+        cached.clone()
+    }
+
+    /// The schema, if it was already fetched or revalidated in this session.
+    pub fn current(&self) -> Option<Arc<Schema>> {
+        self.get()
+            .filter(|cached| cached.current)
+            .map(|cached| cached.schema)
+    }
+
+    /// Keeps using the cached schema for the rest of the session, after the
+    /// server reported it unchanged or could not be reached.
+    pub fn reuse(&self) -> Option<Arc<Schema>> {
+        self.get()?;
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        let cached = cached.as_mut()?;
+        cached.current = true;
+        Some(cached.schema.clone())
+    }
+
+    /// Replaces the cached schema with a freshly fetched one.
+    ///
+    /// The schema is kept in memory even if it cannot be written to disk,
+    /// in which case the next session downloads it again.
+    pub fn store(&self, etag: Option<String>, schema: Schema) -> Arc<Schema> {
+        if let Some(ref path) = self.path {
+            let _ = write_atomically(path, &json!({ "etag": etag, "schema": schema }));
+        }
+        let schema = Arc::new(schema);
+        *self.cached.lock().unwrap_or_else(|e| e.into_inner()) = Some(CachedSchema {
+            etag,
+            schema: schema.clone(),
+            current: true,
+        });
+        schema
+    }
+
+    fn read(&self) -> Option<CacheFile> {
+        let contents = fs::read(self.path.as_ref()?).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+}
+
+/// Writes through a temporary file so that readers never see a partial cache.
+fn write_atomically(path: &Path, contents: &Value) -> Result<(), ZoteroError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    fs::write(&temp, serde_json::to_vec(contents)?)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
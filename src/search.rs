@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::ZoteroError;
+use crate::models::{Collection, Item, SavedSearchData, SearchCondition, SearchOperator};
+use crate::query::Query;
+
+/// Conditions that change how a search is run rather than matching items.
+/// Their setting is sent as the operator, e.g. `recursive` `true`.
+const MODE_CONDITIONS: [&str; 5] = [
+    "joinMode",
+    "noChildren",
+    "includeParentsAndChildren",
+    "recursive",
+    "deleted",
+];
+
+/// Conditions that depend on data the web API does not return with items.
+const UNSUPPORTED_CONDITIONS: [&str; 10] = [
+    "savedSearch",
+    "note",
+    "childNote",
+    "fulltextContent",
+    "fulltextWord",
+    "annotationText",
+    "annotationComment",
+    "attachmentContent",
+    "attachmentFileType",
+    "fileTypeID",
+];
+
+impl SavedSearchData {
+    /// Runs the search's conditions against `items`, returning the matching
+    /// items in their original order.
+    ///
+    /// `collections` is only needed to expand `collection` conditions of
+    /// recursive searches into subcollections. Conditions that need data the
+    /// API does not provide, such as full-text content, are rejected with
+    /// [`ZoteroError::UnsupportedSearchCondition`].
+    pub fn execute<'a>(
+        &self,
+        items: &'a [Item],
+        collections: &[Collection],
+    ) -> Result<Vec<&'a Item>, ZoteroError> {
+        let search = Search::new(self, collections)?;
+        let mut keys: BTreeSet<&str> = items
+            .iter()
+            .filter(|item| search.matches(item))
+            .map(|item| item.key.as_str())
+            .collect();
+
+        if search.flag("includeParentsAndChildren") {
+            let matched = keys.clone();
+            for item in items {
+                let parent = item.data.parent_item.as_deref();
+                if parent.is_some_and(|parent| matched.contains(parent)) {
+                    keys.insert(&item.key);
+                }
+                if matched.contains(item.key.as_str()) {
+                    keys.extend(parent);
+                }
+            }
+        }
+        Ok(items
+            .iter()
+            .filter(|item| keys.contains(item.key.as_str()))
+            .collect())
+    }
+}
+
+struct Search<'s> {
+    conditions: Vec<&'s SearchCondition>,
+    modes: BTreeMap<&'s str, &'s str>,
+    /// Collection keys matched by each `collection` condition, including
+    /// subcollections for recursive searches.
+    collections: BTreeMap<&'s str, BTreeSet<String>>,
+    today: i64,
+}
+
+impl<'s> Search<'s> {
+    fn new(data: &'s SavedSearchData, collections: &[Collection]) -> Result<Self, ZoteroError> {
+        let mut search = Search {
+            conditions: Vec::new(),
+            modes: BTreeMap::new(),
+            collections: BTreeMap::new(),
+            today: today(),
+        };
+        for condition in &data.conditions {
+            let name = condition.condition.as_str();
+            if MODE_CONDITIONS.contains(&name) {
+                search.modes.insert(name, condition.operator.as_str());
+            } else if UNSUPPORTED_CONDITIONS.contains(&name) {
+                return Err(ZoteroError::UnsupportedSearchCondition(name.to_string()));
+            } else if name != "unfiled" && matches!(condition.operator, SearchOperator::Other(_)) {
+                return Err(ZoteroError::UnsupportedSearchCondition(format!(
+                    "{} {}",
+                    name,
+                    condition.operator.as_str()
+                )));
+            } else {
+                search.conditions.push(condition);
+            }
+        }
+
+        let recursive = search.flag("recursive");
+        for condition in &search.conditions {
+            if condition.condition == "collection" {
+                let mut keys = BTreeSet::from([condition.value.clone()]);
+                if recursive {
+                    add_subcollections(&mut keys, collections);
+                }
+                search.collections.insert(&condition.value, keys);
+            }
+        }
+        Ok(search)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.modes.get(name) == Some(&"true")
+    }
+
+    fn matches(&self, item: &Item) -> bool {
+        let deleted =
+            item.data.other.get("deleted").is_some_and(|deleted| {
+                deleted.as_bool() == Some(true) || deleted.as_i64() == Some(1)
+            });
+        if deleted && !self.flag("deleted") {
+            return false;
+        }
+        if self.flag("noChildren") && item.data.parent_item.is_some() {
+            return false;
+        }
+        if self.conditions.is_empty() {
+            return true;
+        }
+        let mut results = self
+            .conditions
+            .iter()
+            .map(|c| self.condition_matches(c, item));
+        if self.modes.get("joinMode") == Some(&"any") {
+            results.any(|matched| matched)
+        } else {
+            results.all(|matched| matched)
+        }
+    }
+
+    fn condition_matches(&self, condition: &SearchCondition, item: &Item) -> bool {
+        match condition.condition.as_str() {
+            "collection" => {
+                let keys = &self.collections[condition.value.as_str()];
+                let in_collection = item.data.collections.iter().any(|key| keys.contains(key));
+                match condition.operator {
+                    SearchOperator::IsNot => !in_collection,
+                    _ => in_collection,
+                }
+            }
+            "unfiled" => {
+                item.data.collections.is_empty() == (condition.operator.as_str() == "true")
+            }
+            name => compare(
+                &condition.operator,
+                &field_values(name, item),
+                &condition.value,
+                self.today,
+            ),
+        }
+    }
+}
+
+fn add_subcollections(keys: &mut BTreeSet<String>, collections: &[Collection]) {
+    loop {
+        let children: Vec<String> = collections
+            .iter()
+            .filter(|c| !keys.contains(&c.key))
+            .filter(|c| {
+                c.data
+                    .parent_collection
+                    .as_ref()
+                    .is_some_and(|p| keys.contains(p))
+            })
+            .map(|c| c.key.clone())
+            .collect();
+        if children.is_empty() {
+            return;
+        }
+        keys.extend(children);
+    }
+}
+
+/// Values of an item that a condition is compared against.
+fn field_values(condition: &str, item: &Item) -> Vec<String> {
+    let data = &item.data;
+    match condition {
+        "title" => data.title.iter().cloned().collect(),
+        "itemType" => vec![data.item_type.clone()],
+        "tag" => data.tags.iter().map(|t| t.tag.clone()).collect(),
+        "creator" => data
+            .creators
+            .iter()
+            .map(|c| match c.name {
+                Some(ref name) => name.clone(),
+                None => format!(
+                    "{} {}",
+                    c.first_name.as_deref().unwrap_or_default(),
+                    c.last_name.as_deref().unwrap_or_default()
+                )
+                .trim()
+                .to_string(),
+            })
+            .collect(),
+        "lastName" => data
+            .creators
+            .iter()
+            .filter_map(|c| c.last_name.clone().or_else(|| c.name.clone()))
+            .collect(),
+        "firstName" => data
+            .creators
+            .iter()
+            .filter_map(|c| c.first_name.clone())
+            .collect(),
+        "dateAdded" => data.date_added.iter().cloned().collect(),
+        "dateModified" => data.date_modified.iter().cloned().collect(),
+        "date" => item_date(item).into_iter().collect(),
+        "year" => item_date(item)
+            .map(|date| date.chars().take(4).collect())
+            .into_iter()
+            .collect(),
+        "quicksearch-titleCreatorYear" => {
+            let mut values = field_values("title", item);
+            values.extend(field_values("creator", item));
+            values.extend(field_values("year", item));
+            values
+        }
+        "quicksearch-fields" | "quicksearch-all" | "quicksearch-everything" => {
+            let mut values = field_values("quicksearch-titleCreatorYear", item);
+            values.extend(field_values("tag", item));
+            values.extend(data.other.values().filter_map(value_string));
+            values
+        }
+        name => data
+            .other
+            .get(name)
+            .and_then(value_string)
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn value_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// The item's date, preferring the `parsedDate` the API adds to `meta`.
+fn item_date(item: &Item) -> Option<String> {
+    item.meta
+        .get("parsedDate")
+        .and_then(value_string)
+        .or_else(|| item.data.other.get("date").and_then(value_string))
+}
+
+fn compare(operator: &SearchOperator, values: &[String], expected: &str, today: i64) -> bool {
+    let expected_lower = expected.to_lowercase();
+    let text = |test: &dyn Fn(&str) -> bool| values.iter().any(|v| test(&v.to_lowercase()));
+    let number = |test: &dyn Fn(f64, f64) -> bool| match expected.trim().parse::<f64>() {
+        Ok(expected) => values
+            .iter()
+            .filter_map(|v| v.trim().parse::<f64>().ok())
+            .any(|v| test(v, expected)),
+        Err(_) => false,
+    };
+    let date = |test: &dyn Fn(i64) -> bool| values.iter().filter_map(|v| parse_days(v)).any(test);
+    match operator {
+        SearchOperator::Is => text(&|v| v == expected_lower),
+        SearchOperator::IsNot => !text(&|v| v == expected_lower),
+        SearchOperator::BeginsWith => text(&|v| v.starts_with(&expected_lower)),
+        SearchOperator::Contains => text(&|v| v.contains(&expected_lower)),
+        SearchOperator::DoesNotContain => !text(&|v| v.contains(&expected_lower)),
+        SearchOperator::IsLessThan => number(&|v, e| v < e),
+        SearchOperator::IsGreaterThan => number(&|v, e| v > e),
+        SearchOperator::IsBefore => match parse_days(expected) {
+            Some(limit) => date(&|days| days < limit),
+            None => false,
+        },
+        SearchOperator::IsAfter => match parse_days(expected) {
+            Some(limit) => date(&|days| days > limit),
+            None => false,
+        },
+        SearchOperator::IsInTheLast => match parse_period(expected) {
+            Some(period) => date(&|days| days >= today - period),
+            None => false,
+        },
+        SearchOperator::Other(_) => false,
+    }
+}
+
+/// Days since the Unix epoch of a date starting with `YYYY`, `YYYY-MM` or
+/// `YYYY-MM-DD`. Missing parts count as the start of the year or month.
+fn parse_days(date: &str) -> Option<i64> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year = parts.next()?.get(..4)?.parse::<i64>().ok()?;
+    let number = |part: Option<&str>| {
+        part.and_then(|p| p.get(..2))
+            .and_then(|p| p.parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(1)
+    };
+    let month = number(parts.next());
+    let day = number(parts.next());
+    Some(days_from_civil(year, month, day))
+}
+
+/// Length in days of a period such as `7 days` or `2 months`. Months and
+/// years are counted as 30 and 365 days.
+fn parse_period(period: &str) -> Option<i64> {
+    let mut parts = period.split_whitespace();
+    let count = parts.next()?.parse::<i64>().ok()?;
+    let unit = parts.next().unwrap_or("days").trim_end_matches('s');
+    let days = match unit {
+        "day" => 1,
+        "week" => 7,
+        "month" => 30,
+        "year" => 365,
+        _ => return None,
+    };
+    Some(count * days)
+}
+
+fn today() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    (seconds / 86_400) as i64
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+impl SavedSearchData {
+    fn mode(&self, name: &str) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| c.condition == name && c.operator.as_str() == "true")
+    }
+
+    /// Parameters for fetching the items the search runs against.
+    pub(crate) fn item_query(&self) -> Query {
+        let query = Query::new().limit(100);
+        match self.mode("deleted") {
+            true => query.include_trashed(),
+            false => query,
+        }
+    }
+
+    /// Whether evaluating the search needs the library's collections.
+    pub(crate) fn needs_collections(&self) -> bool {
+        self.mode("recursive") && self.conditions.iter().any(|c| c.condition == "collection")
+    }
+}
@@ -0,0 +1,204 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::errors::ZoteroError;
+use crate::upload::FileInfo;
+
+const ZIP_CONTENT_TYPES: [&str; 2] = ["application/zip", "application/x-zip-compressed"];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// The file of an attachment item, as returned by `get_attachment_file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentFile {
+    /// A single file, returned as stored.
+    File(bytes::Bytes),
+    /// A web page snapshot that was stored as a ZIP archive.
+    Snapshot(Snapshot),
+}
+
+/// The files of a web page snapshot, keyed by their `/`-separated path
+/// inside the snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub files: BTreeMap<String, Vec<u8>>,
+    /// Path of the HTML page the snapshot opens with.
+    pub main_file: Option<String>,
+}
+
+impl Snapshot {
+    pub fn new(files: BTreeMap<String, Vec<u8>>, main_file: &str) -> Self {
+        Snapshot {
+            files,
+            main_file: Some(main_file.to_string()),
+        }
+    }
+
+    /// Reads a snapshot from a ZIP archive.
+    ///
+    /// `filename` is the attachment's `filename` field. It is used as the main
+    /// file if the archive contains it; otherwise `index.html` or the least
+    /// nested HTML file is used.
+    pub fn from_zip(data: &[u8], filename: Option<&str>) -> Result<Self, ZoteroError> {
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+        let mut files = BTreeMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry
+                .enclosed_name()
+                .and_then(|path| relative_name(&path))
+                .ok_or_else(|| ZoteroError::UnsafePath(entry.name().to_string()))?;
+            let mut contents = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut contents)?;
+            files.insert(name, contents);
+        }
+        let main_file = main_file(&files, filename);
+        Ok(Snapshot { files, main_file })
+    }
+
+    /// Reads every file below `dir` into a snapshot that opens with `main_file`.
+    pub fn from_dir(dir: &Path, main_file: &str) -> Result<Self, ZoteroError> {
+        let mut files = BTreeMap::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if let Some(name) = path.strip_prefix(dir).ok().and_then(relative_name) {
+                    files.insert(name, fs::read(&path)?);
+                }
+            }
+        }
+        Ok(Self::new(files, main_file))
+    }
+
+    /// Contents of the main HTML page.
+    pub fn main_html(&self) -> Option<&[u8]> {
+        self.main_file
+            .as_ref()
+            .and_then(|name| self.files.get(name))
+            .map(Vec::as_slice)
+    }
+
+    /// Writes the files below `dir` and returns the path of the main page.
+    pub fn extract_to(&self, dir: &Path) -> Result<Option<PathBuf>, ZoteroError> {
+        for (name, contents) in &self.files {
+            let path = dir.join(safe_path(name)?);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, contents)?;
+        }
+        Ok(self.main_file.as_ref().map(|name| dir.join(name)))
+    }
+
+    /// Packs the files into a ZIP archive, as expected by the upload endpoint.
+    pub fn to_zip(&self) -> Result<Vec<u8>, ZoteroError> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, contents) in &self.files {
+            safe_path(name)?;
+            writer.start_file(name.as_str(), options)?;
+            writer.write_all(contents)?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    /// File info of the main page, which the server records for the attachment.
+    pub fn file_info(&self) -> Result<FileInfo, ZoteroError> {
+        let name = self.main_file.as_deref().unwrap_or_default();
+        let contents = self.main_html().ok_or_else(|| {
+            ZoteroError::UploadError(format!("Snapshot has no main file {:?}", name))
+        })?;
+        let mtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let filename = name.rsplit('/').next().unwrap_or(name);
+        Ok(FileInfo::new(filename, contents, mtime))
+    }
+
+    /// Item data for a new `imported_url` attachment holding a snapshot of `url`.
+    pub fn attachment_item(
+        &self,
+        url: &str,
+        parent_item: Option<&str>,
+    ) -> Result<Value, ZoteroError> {
+        let mut item = self.file_info()?.attachment_item(parent_item);
+        item["linkMode"] = Value::String("imported_url".to_string());
+        item["contentType"] = Value::String("text/html".to_string());
+        item["url"] = Value::String(url.to_string());
+        Ok(item)
+    }
+}
+
+/// Whether a downloaded attachment file is a zipped snapshot, judging by the
+/// item's `linkMode` and `contentType` and the response `Content-Type`.
+///
+/// Only snapshots (`imported_url`) are unpacked. Other attachments, including
+/// ZIP files and ZIP-based formats such as EPUB, are stored as-is.
+pub fn is_zipped(content_type: Option<&str>, item: &Value, contents: &[u8]) -> bool {
+    if item["data"]["linkMode"] != "imported_url" {
+        return false;
+    }
+    let content_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    content_type.is_some_and(|value| ZIP_CONTENT_TYPES.contains(&value))
+        || (item["data"]["contentType"] == "text/html" && contents.starts_with(ZIP_MAGIC))
+}
+
+/// Form fields added to an upload authorization request for a zipped upload.
+pub(crate) fn zip_form(item_key: &str, info: &FileInfo, zip: &[u8]) -> Vec<(&'static str, String)> {
+    let mut form = info.form();
+    form.push(("zipMD5", format!("{:x}", md5::compute(zip))));
+    form.push(("zipFilename", format!("{}.zip", item_key)));
+    form
+}
+
+fn main_file(files: &BTreeMap<String, Vec<u8>>, filename: Option<&str>) -> Option<String> {
+    if let Some(name) = filename.filter(|name| files.contains_key(*name)) {
+        return Some(name.to_string());
+    }
+    if files.contains_key("index.html") {
+        return Some("index.html".to_string());
+    }
+    files
+        .keys()
+        .filter(|name| {
+            let lower = name.to_ascii_lowercase();
+            lower.ends_with(".html") || lower.ends_with(".htm")
+        })
+        .min_by_key(|name| name.matches('/').count())
+        .cloned()
+}
+
+/// Joins the components of a relative path with `/`.
+fn relative_name(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn safe_path(name: &str) -> Result<PathBuf, ZoteroError> {
+    let path = Path::new(name);
+    if relative_name(path).is_none() {
+        return Err(ZoteroError::UnsafePath(name.to_string()));
+    }
+    Ok(path.to_path_buf())
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::errors::ZoteroError;
+use crate::query::{Format, Query};
+
+/// Number of objects requested per batch when downloading changed objects.
+pub(crate) const SYNC_BATCH_SIZE: usize = 50;
+/// Number of times a sync is restarted when the library changes mid-sync.
+pub(crate) const MAX_SYNC_ATTEMPTS: u8 = 3;
+
+/// Kinds of synced library objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    Collection,
+    Search,
+    Item,
+}
+
+impl ObjectKind {
+    pub const ALL: [ObjectKind; 3] = [ObjectKind::Collection, ObjectKind::Search, ObjectKind::Item];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            ObjectKind::Collection => "collections",
+            ObjectKind::Search => "searches",
+            ObjectKind::Item => "items",
+        }
+    }
+
+    pub(crate) fn versions_query(&self, since: i64) -> Query {
+        let query = Query::new().since(since).format(Format::Versions);
+        match self {
+            ObjectKind::Item => query.include_trashed(),
+            _ => query,
+        }
+    }
+
+    pub(crate) fn batch_query(&self, keys: &[&str]) -> Query {
+        let query = Query::new().limit(SYNC_BATCH_SIZE as u32);
+        match self {
+            ObjectKind::Collection => query.collection_keys(keys),
+            ObjectKind::Search => query.search_keys(keys),
+            ObjectKind::Item => query.item_keys(keys).include_trashed(),
+        }
+    }
+}
+
+/// Tombstones returned by the `/deleted` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deleted {
+    #[serde(default)]
+    pub collections: Vec<String>,
+    #[serde(default)]
+    pub searches: Vec<String>,
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub settings: Vec<String>,
+}
+
+impl Deleted {
+    pub fn keys(&self, kind: ObjectKind) -> &[String] {
+        match kind {
+            ObjectKind::Collection => &self.collections,
+            ObjectKind::Search => &self.searches,
+            ObjectKind::Item => &self.items,
+        }
+    }
+}
+
+/// Changes to a library between two versions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncResult {
+    /// Version the changes were requested since.
+    pub since: i64,
+    /// Library version the changes bring a local copy up to.
+    pub version: i64,
+    pub changed: BTreeMap<ObjectKind, Vec<Value>>,
+    pub deleted: Deleted,
+}
+
+impl SyncResult {
+    pub(crate) fn new(since: i64) -> Self {
+        SyncResult {
+            since,
+            version: since,
+            ..Default::default()
+        }
+    }
+
+    pub fn changed(&self, kind: ObjectKind) -> &[Value] {
+        self.changed.get(&kind).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Applies the tombstones and changed objects to `store` and records the new version.
+    pub fn apply<S: SyncStore>(&self, store: &mut S) -> Result<(), ZoteroError> {
+        for kind in ObjectKind::ALL {
+            let deleted = self.deleted.keys(kind);
+            if !deleted.is_empty() {
+                store.delete(kind, deleted)?;
+            }
+            let changed = self.changed(kind);
+            if !changed.is_empty() {
+                store.upsert(kind, changed)?;
+            }
+        }
+        store.set_library_version(self.version)
+    }
+}
+
+/// Local storage that can be kept up to date with [`SyncResult::apply`].
+pub trait SyncStore {
+    /// Library version the store was last synced to, or 0 if never synced.
+    fn library_version(&self) -> Result<i64, ZoteroError>;
+    fn upsert(&mut self, kind: ObjectKind, objects: &[Value]) -> Result<(), ZoteroError>;
+    fn delete(&mut self, kind: ObjectKind, keys: &[String]) -> Result<(), ZoteroError>;
+    fn set_library_version(&mut self, version: i64) -> Result<(), ZoteroError>;
+}
+
+/// In-memory [`SyncStore`], keyed by object key.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryStore {
+    pub version: i64,
+    pub objects: BTreeMap<ObjectKind, BTreeMap<String, Value>>,
+}
+
+impl MemoryStore {
+    pub fn get(&self, kind: ObjectKind, key: &str) -> Option<&Value> {
+        self.objects.get(&kind).and_then(|objects| objects.get(key))
+    }
+}
+
+impl SyncStore for MemoryStore {
+    fn library_version(&self) -> Result<i64, ZoteroError> {
+        Ok(self.version)
+    }
+
+    fn upsert(&mut self, kind: ObjectKind, objects: &[Value]) -> Result<(), ZoteroError> {
+        let entries = self.objects.entry(kind).or_default();
+        for object in objects {
+            if let Some(key) = object["key"].as_str() {
+                entries.insert(key.to_string(), object.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, kind: ObjectKind, keys: &[String]) -> Result<(), ZoteroError> {
+        if let Some(entries) = self.objects.get_mut(&kind) {
+            for key in keys {
+                entries.remove(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_library_version(&mut self, version: i64) -> Result<(), ZoteroError> {
+        self.version = version;
+        Ok(())
+    }
+}
+
+/// Records the library version of the first response of a sync and fails if a
+/// later response reports a different one.
+pub(crate) fn check_version(
+    expected: &mut Option<i64>,
+    actual: Option<i64>,
+) -> Result<(), ZoteroError> {
+    match (*expected, actual) {
+        (None, actual) => {
+            *expected = actual;
+            Ok(())
+        }
+        (Some(expected), Some(actual)) if expected != actual => {
+            Err(ZoteroError::SyncConflict(format!(
+                "library version changed from {} to {} during sync",
+                expected, actual
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the keys of a `format=versions` response.
+pub(crate) fn version_keys(versions: &Value) -> Vec<String> {
+    versions
+        .as_object()
+        .map(|map| map.keys().cloned().collect())
+        .unwrap_or_default()
+}
@@ -1,59 +1,110 @@
 use bytes::Bytes;
-use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
-use reqwest::Url;
-use serde_json::Value;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, RANGE,
+    USER_AGENT,
+};
+use reqwest::{Method, Url};
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::Arc;
 use std::vec::IntoIter;
 use thiserror::Error;
 
+use crate::access::{AccessibleLibrary, KeyAccess};
+use crate::atom::Feed;
+use crate::backoff::Backoff;
+use crate::bibliography::{citation_query, Bibliography, FormattedItem};
+use crate::builder::{ClientConfig, ZoteroBuilder};
+use crate::download::{finish_download, part_path, resume_offset, ExpectedFile, FileDownload};
 use crate::errors::ZoteroError;
-use crate::{API_VERSION, VERSION};
+use crate::export::{export_query, is_json, is_text, merge_csljson, merge_export};
+use crate::library::Library;
+use crate::models::{Collection, Group, Item, SavedSearch, Tag};
+use crate::pagination::{Listing, Page};
+use crate::query::{Direction, Format, Include, Query, Sort};
+use crate::rate_limit::RateLimiter;
+use crate::retry::{is_replay_safe, server_delay, RetryPolicy};
+use crate::schema::{Schema, SchemaCache};
+use crate::snapshot::{is_zipped, zip_form, AttachmentFile, Snapshot};
+use crate::sync::{
+    check_version, version_keys, ObjectKind, SyncResult, SyncStore, MAX_SYNC_ATTEMPTS,
+    SYNC_BATCH_SIZE,
+};
+use crate::upload::{
+    bsdiff_patch, created_key, patch_unsupported, AttachmentUpload, AuthorizationResponse,
+    FileInfo, PatchAlgorithm, PatchAttempt, UploadAuthorization, UploadOutcome,
+};
+use crate::validate::{ItemTypeSchema, Violation};
+use crate::write::{
+    item_collections, last_modified_version, write_token, WriteResponse, WRITE_TOKEN,
+};
+use crate::API_VERSION;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Zotero {
     client: Client,
-    api_key: String,
+    api_key: Option<String>,
     endpoint: String,
-    pub library_id: String,
-    pub library_type: String,
+    library: Library,
     locale: Option<String>,
-    max_retries: u8,
+    user_agent: String,
+    retry: RetryPolicy,
+    backoff: Backoff,
+    rate_limiter: Option<RateLimiter>,
+    schema: SchemaCache,
 }
 
 impl Zotero {
     pub fn user_lib(user_id: &str, api_key: &str) -> Result<Self, ZoteroError> {
-        Self::new(
-            user_id.to_string(),
-            "users".to_string(),
-            api_key.to_string(),
-        )
+        Self::new(Library::from_parts("users", user_id)?, api_key)
     }
 
     pub fn group_lib(library_id: &str, api_key: &str) -> Result<Self, ZoteroError> {
-        Self::new(
-            library_id.to_string(),
-            "groups".to_string(),
-            api_key.to_string(),
-        )
-    }
-
-    pub fn new(
-        library_id: String,
-        library_type: String,
-        api_key: String,
-    ) -> Result<Self, ZoteroError> {
-        let endpoint = "https://api.zotero.org".to_string();
-        Ok(Zotero {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()?,
-            api_key,
-            endpoint,
-            library_id,
-            library_type,
-            locale: Some("en-US".to_string()),
-            max_retries: 5,
-        })
+        Self::new(Library::from_parts("groups", library_id)?, api_key)
+    }
+
+    pub fn new(library: Library, api_key: &str) -> Result<Self, ZoteroError> {
+        ZoteroBuilder::new(api_key).library(library).build()
+    }
+
+    /// Starts building a client with non-default settings.
+    pub fn builder(api_key: &str) -> ZoteroBuilder {
+        ZoteroBuilder::new(api_key)
+    }
+
+    pub(crate) fn from_config(client: Client, config: ClientConfig) -> Self {
+        Zotero {
+            client,
+            api_key: config.api_key,
+            endpoint: config.endpoint,
+            library: config.library,
+            locale: config.locale,
+            user_agent: config.user_agent,
+            retry: config.retry,
+            backoff: Backoff::default(),
+            rate_limiter: config.rate_limiter,
+            schema: SchemaCache::new(config.schema_cache),
+        }
+    }
+
+    pub fn library(&self) -> Library {
+        self.library
+    }
+
+    /// Points the client at another library, keeping its HTTP client,
+    /// settings and shared rate limiter.
+    pub fn set_library(&mut self, library: Library) {
+        self.library = library;
+    }
+
+    /// Returns a copy of the client pointed at another library.
+    pub fn for_library(&self, library: Library) -> Self {
+        Zotero {
+            library,
+            ..self.clone()
+        }
     }
 
     pub fn set_endpoint(&mut self, endpoint: &str) {
@@ -64,121 +115,286 @@ impl Zotero {
         self.locale = Some(locale.to_string());
     }
 
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Returns the client with requests retried according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    pub fn set_rate_limiter(&mut self, limiter: Option<RateLimiter>) {
+        self.rate_limiter = limiter;
+    }
+
+    /// Returns the client with requests throttled by `limiter`, which is
+    /// shared with every clone of the client.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Caches the schema in `path`, or only in memory if `None`.
+    pub fn set_schema_cache(&mut self, path: Option<&Path>) {
+        self.schema = SchemaCache::new(path.map(Path::to_path_buf));
+    }
+
     fn default_headers(&self) -> Result<HeaderMap, ZoteroError> {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            USER_AGENT,
-            HeaderValue::from_str(&format!("zotero-rust/{}", VERSION))?,
-        );
+        headers.insert(USER_AGENT, HeaderValue::from_str(&self.user_agent)?);
         headers.insert("Zotero-API-Version", HeaderValue::from_str(API_VERSION)?);
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.api_key))?,
-        );
+        if let Some(ref api_key) = self.api_key {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", api_key))?,
+            );
+        }
         Ok(headers)
     }
 
-    fn build_url(&self, path: &str, params: Option<&[(&str, &str)]>) -> Result<Url, ZoteroError> {
-        let mut url = Url::parse(&format!(
-            "{}/{}/{}/{}",
-            self.endpoint, self.library_type, self.library_id, path
-        ))?;
+    fn build_url(&self, path: &str, params: Option<&Query>) -> Result<Url, ZoteroError> {
+        let mut url = Url::parse(&format!("{}/{}/{}", self.endpoint, self.library, path))?;
         if let Some(ref loc) = self.locale {
-            url.query_pairs_mut().append_pair("locale", loc);
+            if params.and_then(|p| p.get("locale")).is_none() {
+                url.query_pairs_mut().append_pair("locale", loc);
+            }
         }
         if let Some(params) = params {
             let mut pairs = url.query_pairs_mut();
-            for &(key, value) in params {
+            for (key, value) in params.pairs() {
                 pairs.append_pair(key, value);
             }
         }
         Ok(url)
     }
 
-    fn build_url_no_lib(
-        &self,
-        path: &str,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<Url, ZoteroError> {
+    fn build_url_no_lib(&self, path: &str, params: Option<&Query>) -> Result<Url, ZoteroError> {
         let mut url = Url::parse(&format!("{}/{}", self.endpoint, path))?;
         if let Some(ref loc) = self.locale {
-            url.query_pairs_mut().append_pair("locale", loc);
+            if params.and_then(|p| p.get("locale")).is_none() {
+                url.query_pairs_mut().append_pair("locale", loc);
+            }
         }
         if let Some(params) = params {
             let mut pairs = url.query_pairs_mut();
-            for &(key, value) in params {
+            for (key, value) in params.pairs() {
                 pairs.append_pair(key, value);
             }
         }
         Ok(url)
     }
 
-    fn handle_response(&self, url: Url) -> Result<Value, ZoteroError> {
-        let mut attempts = 0;
-        let mut backoff = 0.0;
-        while attempts < self.max_retries {
-            let response = self
-                .client
-                .get(url.clone())
-                .headers(self.default_headers()?)
-                .send()?;
-
-            if let Some(bo) = response.headers().get("backoff") {
-                if let Ok(val) = bo.to_str() {
-                    if let Ok(parsed_backoff) = val.parse::<f64>() {
-                        backoff = parsed_backoff;
-                    }
+    /// Sends a request once the rate limiter allows it and any backoff the
+    /// server asked for has passed.
+    fn send_once(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        if let Some(ref limiter) = self.rate_limiter {
+            std::thread::sleep(limiter.acquire());
+        }
+        if let Some(remaining) = self.backoff.remaining() {
+            std::thread::sleep(remaining);
+        }
+        let response = request.send()?;
+        self.backoff.record(response.headers());
+        Ok(response)
+    }
+
+    /// Sends a request, retrying it as allowed by the retry policy.
+    fn send(&self, request: RequestBuilder) -> Result<Response, ZoteroError> {
+        let replay_safe = request
+            .try_clone()
+            .and_then(|request| request.build().ok())
+            .is_some_and(|request| is_replay_safe(request.method(), request.headers()));
+        let mut retry = 0;
+        loop {
+            let attempt = match request.try_clone() {
+                Some(attempt) if self.retry.allows_retry(retry) => attempt,
+                _ => return Ok(self.send_once(request)?),
+            };
+            let delay = match self.send_once(attempt) {
+                Ok(response) if self.retry.retries_status(response.status(), replay_safe) => {
+                    self.retry.delay(retry, server_delay(response.headers()))
                 }
-            } else if let Some(retry_after) = response.headers().get("retry-after") {
-                if let Ok(val) = retry_after.to_str() {
-                    if let Ok(parsed_backoff) = val.parse::<f64>() {
-                        backoff = parsed_backoff;
-                    }
+                Err(error) if self.retry.retries_error(&error, replay_safe) => {
+                    self.retry.delay(retry, None)
                 }
-            }
+                result => return Ok(result?),
+            };
+            std::thread::sleep(delay);
+            retry += 1;
+        }
+    }
 
-            let status = response.status();
-            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                std::thread::sleep(std::time::Duration::from_secs_f64(backoff));
-                attempts += 1;
-                continue;
-            }
+    fn fetch_page(&self, url: Url) -> Result<Page, ZoteroError> {
+        let response = self.send(self.client.get(url).headers(self.default_headers()?))?;
 
-            let content_type = response
-                .headers()
-                .get(reqwest::header::CONTENT_TYPE)
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("");
-
-            if content_type.starts_with("application/json") {
-                let json: Value = response.json()?;
-                return Ok(json);
-            } else if content_type.starts_with("text/html") {
-                let text = response.text()?;
-                return Ok(Value::String(text));
-            } else {
-                return Err(ZoteroError::UnsupportedContentType(
-                    content_type.to_string(),
-                ));
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Self::response_error(response));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        let headers = response.headers().clone();
+        if is_json(content_type) {
+            let json: Value = response.json()?;
+            Ok(Page::new(json, &headers))
+        } else if is_text(content_type) {
+            let text = response.text()?;
+            Ok(Page::new(Value::String(text), &headers))
+        } else {
+            Err(ZoteroError::UnsupportedContentType(
+                content_type.to_string(),
+            ))
+        }
+    }
+
+    /// Converts an unsuccessful response into the matching error variant.
+    fn response_error(response: Response) -> ZoteroError {
+        let status = response.status();
+        let url = response.url().clone();
+        let body = response.text().unwrap_or_default();
+        ZoteroError::from_response(status, &url, body)
+    }
+
+    fn handle_response(&self, url: Url) -> Result<Value, ZoteroError> {
+        Ok(self.fetch_page(url)?.data)
+    }
+
+    /// Fetches a single page of a listing endpoint, including its pagination headers.
+    pub fn get_page(&self, listing: &Listing, params: Option<&Query>) -> Result<Page, ZoteroError> {
+        let url = self.build_url(&listing.path(), params)?;
+        self.fetch_page(url)
+    }
+
+    /// Fetches the page following `page`, if there is one.
+    pub fn next_page(&self, page: &Page) -> Result<Option<Page>, ZoteroError> {
+        match page.links.next {
+            Some(ref url) => Ok(Some(self.fetch_page(url.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches every page of a listing endpoint by following `rel="next"` links.
+    pub fn everything(
+        &self,
+        listing: &Listing,
+        params: Option<&Query>,
+    ) -> Result<Vec<Value>, ZoteroError> {
+        let mut results = Vec::new();
+        let mut page = self.get_page(listing, params)?;
+        loop {
+            match page.data {
+                Value::Array(ref mut values) => results.append(values),
+                ref value => results.push(value.clone()),
+            }
+            match self.next_page(&page)? {
+                Some(next) => page = next,
+                None => return Ok(results),
             }
         }
+    }
 
-        Err(ZoteroError::TooManyRequests(
-            "429: Too Many Requests".to_string(),
-        ))
+    /// Fetches a listing as an Atom feed, e.g. with
+    /// `Query::new().content(&["json"])` for the JSON of each object.
+    pub fn get_feed(&self, listing: &Listing, params: Option<&Query>) -> Result<Feed, ZoteroError> {
+        let query = params.cloned().unwrap_or_default().format(Format::Atom);
+        let url = self.build_url(&listing.path(), Some(&query))?;
+        self.fetch_feed(url)
+    }
+
+    /// Fetches the feed following `feed`, if there is one.
+    pub fn next_feed(&self, feed: &Feed) -> Result<Option<Feed>, ZoteroError> {
+        match feed.links.next {
+            Some(ref url) => Ok(Some(self.fetch_feed(url.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_feed(&self, url: Url) -> Result<Feed, ZoteroError> {
+        match self.fetch_page(url)?.data {
+            Value::String(xml) => Feed::from_xml(&xml),
+            other => Err(ZoteroError::AtomError(format!(
+                "expected XML, got {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn get_key_info(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
+        let Some(ref api_key) = self.api_key else {
+            return Err(ZoteroError::ConfigError("no API key was given".to_string()));
+        };
+        let url = self.build_url_no_lib(&format!("keys/{}", api_key), params)?;
+        self.handle_response(url)
+    }
+
+    /// Lists the groups of the user library the client points at, or of the
+    /// API key's user when it points at a group library.
+    pub fn get_groups(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
+        let user = match self.library {
+            Library::User(_) => self.library,
+            Library::Group(_) => {
+                let info = self.get_key_info(None)?;
+                Library::User(KeyAccess::from_key_info(&info)?.user_id)
+            }
+        };
+        let url = self.for_library(user).build_url("groups", params)?;
+        self.handle_response(url)
     }
 
-    pub fn get_key_info(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
-        let url = self.build_url(&format!("keys/{}", self.api_key), params)?;
+    pub fn get_groups_typed(&self, params: Option<&Query>) -> Result<Vec<Group>, ZoteroError> {
+        Ok(serde_json::from_value(self.get_groups(params)?)?)
+    }
+
+    pub fn get_group(&self, group_id: u64) -> Result<Value, ZoteroError> {
+        let url = self.build_url_no_lib(&format!("groups/{}", group_id), None)?;
         self.handle_response(url)
     }
 
-    pub fn get_top(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
+    /// Returns a client for every library the API key can read, together with
+    /// the key's permissions in it.
+    pub fn get_accessible_libraries(&self) -> Result<Vec<AccessibleLibrary<Self>>, ZoteroError> {
+        let access = KeyAccess::from_key_info(&self.get_key_info(None)?)?;
+        let user = Library::User(access.user_id);
+        let groups = self.for_library(user).everything(&Listing::Groups, None)?;
+        let groups: Vec<Group> = serde_json::from_value(Value::Array(groups))?;
+
+        let mut libraries = Vec::new();
+        if access.user().library {
+            libraries.push(AccessibleLibrary {
+                library: user,
+                name: access.username.clone(),
+                permissions: access.user(),
+                client: self.for_library(user),
+            });
+        }
+        for group in groups {
+            let permissions = access.group(group.id);
+            if permissions.library {
+                let library = Library::Group(group.id);
+                libraries.push(AccessibleLibrary {
+                    library,
+                    name: group.data.name,
+                    permissions,
+                    client: self.for_library(library),
+                });
+            }
+        }
+        Ok(libraries)
+    }
+
+    pub fn get_top(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("items/top", params)?;
         self.handle_response(url)
     }
 
-    pub fn get_collections(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
+    pub fn get_collections(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("collections", params)?;
         self.handle_response(url)
     }
@@ -186,16 +402,13 @@ impl Zotero {
     pub fn get_collection(
         &self,
         collection_id: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("collections/{}", collection_id), params)?;
         self.handle_response(url)
     }
 
-    pub fn get_collections_top(
-        &self,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<Value, ZoteroError> {
+    pub fn get_collections_top(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("collections/top", params)?;
         self.handle_response(url)
     }
@@ -203,7 +416,7 @@ impl Zotero {
     pub fn get_collections_sub(
         &self,
         collection_id: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(
             &format!("collections/{}/collections", collection_id),
@@ -215,22 +428,18 @@ impl Zotero {
     pub fn get_collection_items(
         &self,
         collection_id: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("collections/{}/items", collection_id), params)?;
         self.handle_response(url)
     }
 
-    pub fn get_item(
-        &self,
-        item_id: &str,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<Value, ZoteroError> {
+    pub fn get_item(&self, item_id: &str, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("items/{}", item_id), params)?;
         self.handle_response(url)
     }
 
-    pub fn get_items(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
+    pub fn get_items(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("items", params)?;
         self.handle_response(url)
     }
@@ -238,7 +447,7 @@ impl Zotero {
     pub fn get_fulltext_item(
         &self,
         item_key: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("items/{}/fulltext", item_key), params)?;
         self.handle_response(url)
@@ -247,23 +456,19 @@ impl Zotero {
     pub fn get_new_fulltext(
         &self,
         since: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let mut url = self.build_url("fulltext", params)?;
         url.query_pairs_mut().append_pair("since", since);
         self.handle_response(url)
     }
 
-    pub fn get_trash(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
+    pub fn get_trash(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("items/trash", params)?;
         self.handle_response(url)
     }
 
-    pub fn get_deleted(
-        &self,
-        since: &str,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<Value, ZoteroError> {
+    pub fn get_deleted(&self, since: &str, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let mut url = self.build_url("deleted", params)?;
         url.query_pairs_mut().append_pair("since", since);
         self.handle_response(url)
@@ -272,13 +477,13 @@ impl Zotero {
     pub fn get_children(
         &self,
         item_id: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("items/{}/children", item_id), params)?;
         self.handle_response(url)
     }
 
-    pub fn get_tags(&self, params: Option<&[(&str, &str)]>) -> Result<Value, ZoteroError> {
+    pub fn get_tags(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
         let url = self.build_url("tags", params)?;
         self.handle_response(url)
     }
@@ -286,47 +491,248 @@ impl Zotero {
     pub fn get_item_tags(
         &self,
         item_id: &str,
-        params: Option<&[(&str, &str)]>,
+        params: Option<&Query>,
     ) -> Result<Value, ZoteroError> {
         let url = self.build_url(&format!("items/{}/tags", item_id), params)?;
         self.handle_response(url)
     }
 
-    pub fn get_file(
+    pub fn get_item_typed(
         &self,
         item_id: &str,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<Bytes, ZoteroError> {
+        params: Option<&Query>,
+    ) -> Result<Item, ZoteroError> {
+        Ok(serde_json::from_value(self.get_item(item_id, params)?)?)
+    }
+
+    pub fn get_items_typed(&self, params: Option<&Query>) -> Result<Vec<Item>, ZoteroError> {
+        Ok(serde_json::from_value(self.get_items(params)?)?)
+    }
+
+    pub fn get_collections_typed(
+        &self,
+        params: Option<&Query>,
+    ) -> Result<Vec<Collection>, ZoteroError> {
+        Ok(serde_json::from_value(self.get_collections(params)?)?)
+    }
+
+    pub fn get_tags_typed(&self, params: Option<&Query>) -> Result<Vec<Tag>, ZoteroError> {
+        Ok(serde_json::from_value(self.get_tags(params)?)?)
+    }
+
+    /// Fetches a bibliography of the items matching `params` (e.g. specific
+    /// item keys) formatted in a CSL style such as `apa`.
+    ///
+    /// `locale` overrides the client's locale. The API formats at most 150
+    /// items per request.
+    pub fn get_bibliography(
+        &self,
+        style: &str,
+        locale: Option<&str>,
+        params: Option<&Query>,
+    ) -> Result<Bibliography, ZoteroError> {
+        let query = citation_query(style, locale, params).format(Format::Bib);
+        let url = self.build_url("items", Some(&query))?;
+        match self.handle_response(url)? {
+            Value::String(html) => Ok(Bibliography::from_html(&html)),
+            other => Err(ZoteroError::UnsupportedContentType(format!(
+                "expected a bibliography, got {}",
+                other
+            ))),
+        }
+    }
+
+    /// Fetches the formatted in-text citation and bibliography entry of every
+    /// item matching `params`, following pagination.
+    pub fn get_citations(
+        &self,
+        style: &str,
+        locale: Option<&str>,
+        params: Option<&Query>,
+    ) -> Result<Vec<FormattedItem>, ZoteroError> {
+        let query = citation_query(style, locale, params)
+            .format(Format::Json)
+            .include(&[Include::Citation, Include::Bib]);
+        let items = self.everything(&Listing::Items, Some(&query))?;
+        Ok(items.iter().map(FormattedItem::from_value).collect())
+    }
+
+    /// Exports items in one of the export formats (`Format::Bibtex`,
+    /// `Format::Ris`, ...), following pagination so the whole result is
+    /// returned as a single document.
+    pub fn get_items_formatted(
+        &self,
+        format: Format,
+        params: Option<&Query>,
+    ) -> Result<String, ZoteroError> {
+        let query = export_query(format, params)?;
+        let pages = self.everything(&Listing::Items, Some(&query))?;
+        merge_export(format, pages)
+    }
+
+    /// Exports items as CSL-JSON, merging every page into one `items` array.
+    pub fn get_items_csljson(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
+        let query = export_query(Format::CslJson, params)?;
+        Ok(merge_csljson(
+            self.everything(&Listing::Items, Some(&query))?,
+        ))
+    }
+
+    pub fn get_searches(&self, params: Option<&Query>) -> Result<Value, ZoteroError> {
+        let url = self.build_url("searches", params)?;
+        self.handle_response(url)
+    }
+
+    pub fn get_search(
+        &self,
+        search_key: &str,
+        params: Option<&Query>,
+    ) -> Result<Value, ZoteroError> {
+        let url = self.build_url(&format!("searches/{}", search_key), params)?;
+        self.handle_response(url)
+    }
+
+    pub fn get_searches_typed(
+        &self,
+        params: Option<&Query>,
+    ) -> Result<Vec<SavedSearch>, ZoteroError> {
+        Ok(serde_json::from_value(self.get_searches(params)?)?)
+    }
+
+    /// Runs a saved search against the library's items.
+    ///
+    /// The API stores saved searches but does not execute them, so this
+    /// fetches every item and evaluates the conditions locally.
+    pub fn execute_search(&self, search_key: &str) -> Result<Vec<Item>, ZoteroError> {
+        let search: SavedSearch = serde_json::from_value(self.get_search(search_key, None)?)?;
+        let query = search.data.item_query();
+        let items: Vec<Item> = serde_json::from_value(Value::Array(
+            self.everything(&Listing::Items, Some(&query))?,
+        ))?;
+        let collections: Vec<Collection> = match search.data.needs_collections() {
+            true => {
+                serde_json::from_value(Value::Array(self.everything(&Listing::Collections, None)?))?
+            }
+            false => Vec::new(),
+        };
+        let matches = search.data.execute(&items, &collections)?;
+        Ok(matches.into_iter().cloned().collect())
+    }
+
+    /// Downloads a file, returning the response `Content-Type` with its contents.
+    fn fetch_file(
+        &self,
+        item_id: &str,
+        params: Option<&Query>,
+    ) -> Result<(Option<String>, Bytes), ZoteroError> {
         let url = self.build_url(&format!("items/{}/file", item_id), params)?;
-        let response = self
-            .client
-            .get(url)
-            .headers(self.default_headers()?)
-            .send()?;
+        let response = self.send(self.client.get(url).headers(self.default_headers()?))?;
 
         if response.status().is_success() {
+            let content_type = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
             let bytes = response.bytes()?;
-            Ok(bytes)
+            Ok((content_type, bytes))
         } else {
-            Err(ZoteroError::FileRetrievalError(format!(
-                "Failed to retrieve file: {}",
-                response.status()
-            )))
+            Err(Self::response_error(response))
         }
     }
 
-    pub fn get_last_modified_version(
+    pub fn get_file(&self, item_id: &str, params: Option<&Query>) -> Result<Bytes, ZoteroError> {
+        Ok(self.fetch_file(item_id, params)?.1)
+    }
+
+    /// Downloads the file of an attachment item, unpacking it if it is a
+    /// zipped web page snapshot.
+    pub fn get_attachment_file(&self, item_key: &str) -> Result<AttachmentFile, ZoteroError> {
+        let item = self.get_item(item_key, None)?;
+        let (content_type, contents) = self.fetch_file(item_key, None)?;
+        if is_zipped(content_type.as_deref(), &item, &contents) {
+            let filename = item["data"]["filename"].as_str();
+            Ok(AttachmentFile::Snapshot(Snapshot::from_zip(
+                &contents, filename,
+            )?))
+        } else {
+            Ok(AttachmentFile::File(contents))
+        }
+    }
+
+    fn open_file_stream(
         &self,
-        params: Option<&[(&str, &str)]>,
-    ) -> Result<i64, ZoteroError> {
-        let mut params_with_limit = params.unwrap_or(&[]).to_vec();
-        params_with_limit.push(("limit", "1"));
-        let url = self.build_url("items", Some(params_with_limit.as_slice()))?;
-        let response = self
-            .client
-            .get(url)
-            .headers(self.default_headers()?)
-            .send()?;
+        item_key: &str,
+        offset: u64,
+    ) -> Result<Option<FileDownload<Response>>, ZoteroError> {
+        let url = self.build_url(&format!("items/{}/file", item_key), None)?;
+        let mut headers = self.default_headers()?;
+        if offset > 0 {
+            headers.insert(RANGE, HeaderValue::from_str(&format!("bytes={}-", offset))?);
+        }
+        let response = self.send(self.client.get(url).headers(headers))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            return Ok(None);
+        } else if !status.is_success() {
+            return Err(Self::response_error(response));
+        }
+        let start = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+            offset
+        } else {
+            0
+        };
+        Ok(Some(FileDownload {
+            content_length: response.content_length(),
+            start,
+            reader: response,
+        }))
+    }
+
+    /// Opens the file of an attachment item for streaming, asking the server to
+    /// start at byte `offset`.
+    pub fn download_file_stream(
+        &self,
+        item_key: &str,
+        offset: u64,
+    ) -> Result<FileDownload<Response>, ZoteroError> {
+        let download = self.open_file_stream(item_key, offset)?.ok_or_else(|| {
+            ZoteroError::FileRetrievalError(format!(
+                "Failed to retrieve file: {}",
+                reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+            ))
+        })?;
+        Ok(download)
+    }
+
+    /// Streams the file of an attachment item to `path`, returning its size.
+    ///
+    /// Data is written to `<path>.part` first, so an interrupted download is
+    /// resumed from where it stopped on the next call. The file is checked
+    /// against the item's `md5` and given the item's `mtime` before being moved
+    /// to `path`.
+    pub fn download_file_to(&self, item_key: &str, path: &Path) -> Result<u64, ZoteroError> {
+        let item = self.get_item(item_key, None)?;
+        let expected = ExpectedFile::from_item(&item);
+        let part = part_path(path);
+        let offset = resume_offset(&part);
+        if let Some(download) = self.open_file_stream(item_key, offset)? {
+            let mut file = if download.start == 0 {
+                File::create(&part)?
+            } else {
+                OpenOptions::new().append(true).open(&part)?
+            };
+            let mut reader = download.reader;
+            std::io::copy(&mut reader, &mut file)?;
+        }
+        finish_download(&part, path, &expected)
+    }
+
+    pub fn get_last_modified_version(&self, params: Option<&Query>) -> Result<i64, ZoteroError> {
+        let query = params.cloned().unwrap_or_default().limit(1);
+        let url = self.build_url("items", Some(&query))?;
+        let response = self.send(self.client.get(url).headers(self.default_headers()?))?;
 
         if response.status().is_success() {
             if let Some(last_modified_version) = response.headers().get("last-modified-version") {
@@ -340,10 +746,56 @@ impl Zotero {
                 "Failed to parse last-modified-version header".to_string(),
             ))
         } else {
-            Err(ZoteroError::FileRetrievalError(format!(
-                "Failed to retrieve last modified version: {}",
-                response.status()
-            )))
+            Err(Self::response_error(response))
+        }
+    }
+
+    /// Fetches the schema of item types, fields, creator types, CSL mappings
+    /// and localized names.
+    ///
+    /// The schema is kept in memory and on disk, see
+    /// [`ZoteroBuilder::schema_cache`]. A cached schema is
+    /// revalidated with `If-None-Match`, so an unchanged schema is not
+    /// downloaded again. If the server cannot be reached or fails with a
+    /// server error, the cached schema is returned.
+    pub fn get_schema(&self) -> Result<Arc<Schema>, ZoteroError> {
+        let mut headers = self.default_headers()?;
+        if let Some(etag) = self.schema.get().and_then(|cached| cached.etag) {
+            headers.insert(IF_NONE_MATCH, HeaderValue::from_str(&etag)?);
+        }
+        let url = Url::parse(&format!("{}/schema", self.endpoint))?;
+        let response = match self.send(self.client.get(url).headers(headers)) {
+            Ok(response) => response,
+            Err(error) => return self.schema.reuse().ok_or(error),
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            return self.schema.reuse().ok_or_else(|| {
+                ZoteroError::InvalidResponse("304 Not Modified without a cached schema".to_string())
+            });
+        }
+        if status.is_server_error() {
+            let error = Self::response_error(response);
+            return self.schema.reuse().ok_or(error);
+        }
+        if !status.is_success() {
+            return Err(Self::response_error(response));
+        }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let schema: Schema = response.json()?;
+        Ok(self.schema.store(etag, schema))
+    }
+
+    /// The schema, fetched or revalidated at most once per session.
+    fn schema(&self) -> Result<Arc<Schema>, ZoteroError> {
+        match self.schema.current() {
+            Some(schema) => Ok(schema),
+            None => self.get_schema(),
         }
     }
 
@@ -352,9 +804,10 @@ impl Zotero {
         self.handle_response(url)
     }
 
+    /// Lists the fields of all item types with their localized names, from
+    /// the schema.
     pub fn get_item_fields(&self) -> Result<Value, ZoteroError> {
-        let url = self.build_url_no_lib("itemFields", None)?;
-        self.handle_response(url)
+        Ok(self.schema()?.item_fields_response(self.locale.as_deref()))
     }
 
     pub fn get_creator_fields(&self) -> Result<Value, ZoteroError> {
@@ -362,18 +815,516 @@ impl Zotero {
         self.handle_response(url)
     }
 
+    /// Lists the fields of `item_type` in display order, from the schema.
     pub fn get_item_type_fields(&self, item_type: &str) -> Result<Value, ZoteroError> {
-        let url = self.build_url_no_lib("itemTypeFields", Some(&[("itemType", item_type)]))?;
-        self.handle_response(url)
+        self.schema()?
+            .item_type_fields_response(item_type, self.locale.as_deref())
+            .ok_or_else(|| ZoteroError::UnknownItemType(item_type.to_string()))
     }
 
     pub fn get_item_creator_types(&self, item_type: &str) -> Result<Value, ZoteroError> {
-        let url =
-            self.build_url_no_lib("itemTypeCreatorTypes", Some(&[("itemType", item_type)]))?;
+        let url = self.build_url_no_lib(
+            "itemTypeCreatorTypes",
+            Some(&Query::new().item_type(item_type)),
+        )?;
         self.handle_response(url)
     }
 
-    pub fn get_items_in_batch(&self, since: usize, batch_size: usize) -> ZoteroItemsBatcher {
+    /// Fetches an empty item of `item_type` to fill in before creating it.
+    ///
+    /// Attachment templates need a `link_mode`, such as `imported_file` or
+    /// `linked_url`.
+    pub fn get_item_template(
+        &self,
+        item_type: &str,
+        link_mode: Option<&str>,
+    ) -> Result<Value, ZoteroError> {
+        let mut query = Query::new().item_type(item_type);
+        if let Some(link_mode) = link_mode {
+            query = query.param("linkMode", link_mode);
+        }
+        let url = self.build_url_no_lib("items/new", Some(&query))?;
+        self.handle_response(url)
+    }
+
+    /// The fields and creator types allowed for `item_type`, from the schema.
+    pub fn get_item_type_schema(&self, item_type: &str) -> Result<ItemTypeSchema, ZoteroError> {
+        self.schema()?
+            .item_type_schema(item_type)
+            .ok_or_else(|| ZoteroError::UnknownItemType(item_type.to_string()))
+    }
+
+    /// Checks an item against the fields and creator types of its type
+    /// before it is submitted, reporting every violation in one
+    /// [`ZoteroError::InvalidItem`].
+    pub fn validate_item(&self, item: &Value) -> Result<(), ZoteroError> {
+        let item_type = match item.as_object() {
+            None => return Err(ZoteroError::InvalidItem(vec![Violation::NotAnObject])),
+            Some(object) => object.get("itemType").and_then(Value::as_str),
+        };
+        let item_type =
+            item_type.ok_or_else(|| ZoteroError::InvalidItem(vec![Violation::MissingItemType]))?;
+        self.get_item_type_schema(item_type)?.check(item)
+    }
+
+    fn send_write(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&Value>,
+        version: Option<i64>,
+    ) -> Result<Response, ZoteroError> {
+        let mut headers = self.default_headers()?;
+        if let Some(version) = version {
+            headers.insert(
+                "If-Unmodified-Since-Version",
+                HeaderValue::from_str(&version.to_string())?,
+            );
+        }
+        if method == Method::POST {
+            headers.insert(WRITE_TOKEN, HeaderValue::from_str(&write_token())?);
+        }
+        let mut request = self.client.request(method, url).headers(headers);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = self.send(request)?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            Err(Self::response_error(response))
+        }
+    }
+
+    fn response_version(response: &Response) -> Result<i64, ZoteroError> {
+        last_modified_version(response.headers()).ok_or_else(|| {
+            ZoteroError::WriteError("Failed to parse last-modified-version header".to_string())
+        })
+    }
+
+    /// Creates up to 50 items in a single request.
+    ///
+    /// If `last_modified_version` is given, the request fails with a 412 if the
+    /// library has been modified since that version.
+    pub fn create_items(
+        &self,
+        items: &[Value],
+        last_modified_version: Option<i64>,
+    ) -> Result<WriteResponse, ZoteroError> {
+        let url = self.build_url("items", None)?;
+        let body = Value::Array(items.to_vec());
+        let response = self.send_write(Method::POST, url, Some(&body), last_modified_version)?;
+        Ok(response.json()?)
+    }
+
+    /// Partially updates an item, returning the new library version.
+    pub fn update_item(
+        &self,
+        item_key: &str,
+        data: &Value,
+        version: i64,
+    ) -> Result<i64, ZoteroError> {
+        let url = self.build_url(&format!("items/{}", item_key), None)?;
+        let response = self.send_write(Method::PATCH, url, Some(data), Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Replaces an item with the full `data` given, returning the new library version.
+    pub fn replace_item(
+        &self,
+        item_key: &str,
+        data: &Value,
+        version: i64,
+    ) -> Result<i64, ZoteroError> {
+        let url = self.build_url(&format!("items/{}", item_key), None)?;
+        let response = self.send_write(Method::PUT, url, Some(data), Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Deletes up to 50 items, returning the new library version.
+    pub fn delete_items(&self, item_keys: &[&str], version: i64) -> Result<i64, ZoteroError> {
+        let query = Query::new().item_keys(item_keys);
+        let url = self.build_url("items", Some(&query))?;
+        let response = self.send_write(Method::DELETE, url, None, Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Creates up to 50 collections in a single request.
+    pub fn create_collections(
+        &self,
+        collections: &[Value],
+        last_modified_version: Option<i64>,
+    ) -> Result<WriteResponse, ZoteroError> {
+        let url = self.build_url("collections", None)?;
+        let body = Value::Array(collections.to_vec());
+        let response = self.send_write(Method::POST, url, Some(&body), last_modified_version)?;
+        Ok(response.json()?)
+    }
+
+    /// Updates a collection, e.g. renaming it or changing its `parentCollection`.
+    ///
+    /// Returns the new library version.
+    pub fn update_collection(
+        &self,
+        collection_key: &str,
+        data: &Value,
+        version: i64,
+    ) -> Result<i64, ZoteroError> {
+        let url = self.build_url(&format!("collections/{}", collection_key), None)?;
+        let response = self.send_write(Method::PUT, url, Some(data), Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Deletes up to 50 collections, returning the new library version.
+    pub fn delete_collections(
+        &self,
+        collection_keys: &[&str],
+        version: i64,
+    ) -> Result<i64, ZoteroError> {
+        let query = Query::new().collection_keys(collection_keys);
+        let url = self.build_url("collections", Some(&query))?;
+        let response = self.send_write(Method::DELETE, url, None, Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Creates up to 50 saved searches in a single request.
+    pub fn create_searches(
+        &self,
+        searches: &[Value],
+        last_modified_version: Option<i64>,
+    ) -> Result<WriteResponse, ZoteroError> {
+        let url = self.build_url("searches", None)?;
+        let body = Value::Array(searches.to_vec());
+        let response = self.send_write(Method::POST, url, Some(&body), last_modified_version)?;
+        Ok(response.json()?)
+    }
+
+    /// Deletes up to 50 saved searches, returning the new library version.
+    pub fn delete_searches(&self, search_keys: &[&str], version: i64) -> Result<i64, ZoteroError> {
+        let query = Query::new().search_keys(search_keys);
+        let url = self.build_url("searches", Some(&query))?;
+        let response = self.send_write(Method::DELETE, url, None, Some(version))?;
+        Self::response_version(&response)
+    }
+
+    /// Adds an item to a collection by patching the item's `collections` array.
+    ///
+    /// Returns the new library version, or the item's current version if it was
+    /// already in the collection.
+    pub fn add_to_collection(
+        &self,
+        item_key: &str,
+        collection_key: &str,
+    ) -> Result<i64, ZoteroError> {
+        let item = self.get_item(item_key, None)?;
+        let (mut collections, version) = item_collections(&item)?;
+        if collections.iter().any(|c| c == collection_key) {
+            return Ok(version);
+        }
+        collections.push(collection_key.to_string());
+        self.update_item(item_key, &json!({ "collections": collections }), version)
+    }
+
+    /// Removes an item from a collection by patching the item's `collections` array.
+    ///
+    /// Returns the new library version, or the item's current version if it was
+    /// not in the collection.
+    pub fn remove_from_collection(
+        &self,
+        item_key: &str,
+        collection_key: &str,
+    ) -> Result<i64, ZoteroError> {
+        let item = self.get_item(item_key, None)?;
+        let (mut collections, version) = item_collections(&item)?;
+        let before = collections.len();
+        collections.retain(|c| c != collection_key);
+        if collections.len() == before {
+            return Ok(version);
+        }
+        self.update_item(item_key, &json!({ "collections": collections }), version)
+    }
+
+    fn try_get_changes_since(&self, since: i64) -> Result<SyncResult, ZoteroError> {
+        let mut result = SyncResult::new(since);
+        let mut version = None;
+        for kind in ObjectKind::ALL {
+            let url = self.build_url(kind.path(), Some(&kind.versions_query(since)))?;
+            let page = self.fetch_page(url)?;
+            check_version(&mut version, page.last_modified_version)?;
+            let keys = version_keys(&page.data);
+            let mut objects = Vec::new();
+            for chunk in keys.chunks(SYNC_BATCH_SIZE) {
+                let chunk: Vec<&str> = chunk.iter().map(String::as_str).collect();
+                let url = self.build_url(kind.path(), Some(&kind.batch_query(&chunk)))?;
+                let page = self.fetch_page(url)?;
+                check_version(&mut version, page.last_modified_version)?;
+                objects.extend(page.into_values());
+            }
+            if !objects.is_empty() {
+                result.changed.insert(kind, objects);
+            }
+        }
+        let url = self.build_url("deleted", Some(&Query::new().since(since)))?;
+        let page = self.fetch_page(url)?;
+        check_version(&mut version, page.last_modified_version)?;
+        result.deleted = serde_json::from_value(page.data)?;
+        result.version = version.unwrap_or(since);
+        Ok(result)
+    }
+
+    /// Fetches all collections, searches and items changed since library version
+    /// `since`, along with the objects deleted since then.
+    ///
+    /// If the library is modified while the changes are being downloaded, the
+    /// download is restarted a few times before giving up with
+    /// [`ZoteroError::SyncConflict`].
+    pub fn get_changes_since(&self, since: i64) -> Result<SyncResult, ZoteroError> {
+        let mut attempts = 1;
+        loop {
+            match self.try_get_changes_since(since) {
+                Err(ZoteroError::SyncConflict(_)) if attempts < MAX_SYNC_ATTEMPTS => attempts += 1,
+                result => return result,
+            }
+        }
+    }
+
+    /// Brings `store` up to date with the library, returning the new library version.
+    pub fn sync_store<S: SyncStore>(&self, store: &mut S) -> Result<i64, ZoteroError> {
+        let changes = self.get_changes_since(store.library_version()?)?;
+        changes.apply(store)?;
+        Ok(changes.version)
+    }
+
+    fn send_file_request(
+        &self,
+        item_key: &str,
+        form: &[(&str, String)],
+        previous_md5: Option<&str>,
+    ) -> Result<Response, ZoteroError> {
+        let url = self.build_url(&format!("items/{}/file", item_key), None)?;
+        let mut headers = self.default_headers()?;
+        match previous_md5 {
+            Some(md5) => headers.insert(IF_MATCH, HeaderValue::from_str(md5)?),
+            None => headers.insert(IF_NONE_MATCH, HeaderValue::from_static("*")),
+        };
+        self.send(self.client.post(url).headers(headers).form(form))
+    }
+
+    fn file_request(
+        &self,
+        item_key: &str,
+        form: &[(&str, String)],
+        previous_md5: Option<&str>,
+    ) -> Result<Response, ZoteroError> {
+        let response = self.send_file_request(item_key, form, previous_md5)?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            Err(Self::response_error(response))
+        }
+    }
+
+    /// Sends the file to the storage server and registers the upload.
+    fn complete_upload(
+        &self,
+        item_key: &str,
+        auth: UploadAuthorization,
+        contents: &[u8],
+        previous_md5: Option<&str>,
+    ) -> Result<UploadOutcome, ZoteroError> {
+        let response = self.send(
+            self.client
+                .post(&auth.url)
+                .header(CONTENT_TYPE, &auth.content_type)
+                .body(auth.body(contents)),
+        )?;
+        if !response.status().is_success() {
+            return Err(ZoteroError::UploadError(format!(
+                "Storage server rejected upload: {}",
+                response.status()
+            )));
+        }
+
+        self.file_request(item_key, &[("upload", auth.upload_key)], previous_md5)?;
+        Ok(UploadOutcome::Uploaded)
+    }
+
+    /// Uploads `contents` as the file of an existing attachment item.
+    ///
+    /// `previous_md5` is the hash of the file currently stored for the item, or
+    /// `None` if the item has no file yet.
+    pub fn upload_file(
+        &self,
+        item_key: &str,
+        info: &FileInfo,
+        contents: &[u8],
+        previous_md5: Option<&str>,
+    ) -> Result<UploadOutcome, ZoteroError> {
+        let response = self.file_request(item_key, &info.form(), previous_md5)?;
+        match response.json::<AuthorizationResponse>()? {
+            AuthorizationResponse::Exists { .. } => Ok(UploadOutcome::Exists),
+            AuthorizationResponse::Upload(auth) => {
+                self.complete_upload(item_key, auth, contents, previous_md5)
+            }
+        }
+    }
+
+    fn try_patch(
+        &self,
+        item_key: &str,
+        info: &FileInfo,
+        previous_md5: &str,
+        algorithm: PatchAlgorithm,
+        patch: &[u8],
+    ) -> Result<PatchAttempt, ZoteroError> {
+        let response = self.send_file_request(item_key, &info.form(), Some(previous_md5))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(PatchAttempt::PreconditionFailed);
+        } else if !status.is_success() {
+            return Err(Self::response_error(response));
+        }
+        let auth = match response.json::<AuthorizationResponse>()? {
+            AuthorizationResponse::Exists { .. } => {
+                return Ok(PatchAttempt::Done(UploadOutcome::Exists))
+            }
+            AuthorizationResponse::Upload(auth) => auth,
+        };
+
+        let mut url = self.build_url(&format!("items/{}/file", item_key), None)?;
+        url.query_pairs_mut()
+            .append_pair("algorithm", algorithm.as_str())
+            .append_pair("upload", &auth.upload_key);
+        let mut headers = self.default_headers()?;
+        headers.insert(IF_MATCH, HeaderValue::from_str(previous_md5)?);
+        let response = self.send(self.client.patch(url).headers(headers).body(patch.to_vec()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(PatchAttempt::Done(UploadOutcome::Patched))
+        } else if status == reqwest::StatusCode::PRECONDITION_FAILED {
+            Ok(PatchAttempt::PreconditionFailed)
+        } else if patch_unsupported(status) {
+            Ok(PatchAttempt::Unsupported(auth))
+        } else {
+            Err(Self::response_error(response))
+        }
+    }
+
+    /// Uploads a precomputed `patch` that turns the file with hash `previous_md5`
+    /// into the file described by `info`.
+    pub fn upload_file_patch(
+        &self,
+        item_key: &str,
+        info: &FileInfo,
+        previous_md5: &str,
+        algorithm: PatchAlgorithm,
+        patch: &[u8],
+    ) -> Result<UploadOutcome, ZoteroError> {
+        match self.try_patch(item_key, info, previous_md5, algorithm, patch)? {
+            PatchAttempt::Done(outcome) => Ok(outcome),
+            PatchAttempt::PreconditionFailed => Err(ZoteroError::UploadError(format!(
+                "Stored file does not match md5 {}",
+                previous_md5
+            ))),
+            PatchAttempt::Unsupported(_) => Err(ZoteroError::UploadError(format!(
+                "Server rejected {} patch",
+                algorithm.as_str()
+            ))),
+        }
+    }
+
+    /// Replaces the file of an attachment item whose current contents are
+    /// `previous`, sending only a bsdiff patch when the server accepts it.
+    ///
+    /// Falls back to a full upload if the server rejects the patch or its file
+    /// no longer matches `previous`.
+    pub fn update_attachment_file(
+        &self,
+        item_key: &str,
+        previous: &[u8],
+        info: &FileInfo,
+        contents: &[u8],
+    ) -> Result<UploadOutcome, ZoteroError> {
+        let previous_md5 = format!("{:x}", md5::compute(previous));
+        let patch = bsdiff_patch(previous, contents)?;
+        let attempt = self.try_patch(
+            item_key,
+            info,
+            &previous_md5,
+            PatchAlgorithm::Bsdiff,
+            &patch,
+        )?;
+        match attempt {
+            PatchAttempt::Done(outcome) => Ok(outcome),
+            PatchAttempt::Unsupported(auth) => {
+                self.complete_upload(item_key, auth, contents, Some(&previous_md5))
+            }
+            PatchAttempt::PreconditionFailed => {
+                let item = self.get_item(item_key, None)?;
+                let md5 = item["data"]["md5"].as_str();
+                self.upload_file(item_key, info, contents, md5)
+            }
+        }
+    }
+
+    /// Creates an attachment item for the file at `path` and uploads the file.
+    pub fn upload_attachment(
+        &self,
+        path: &Path,
+        parent_item: Option<&str>,
+    ) -> Result<AttachmentUpload, ZoteroError> {
+        let contents = std::fs::read(path)?;
+        let info = FileInfo::from_path(path, &contents)?;
+        let created = self.create_items(&[info.attachment_item(parent_item)], None)?;
+        let key = created_key(&created)?;
+        let outcome = self.upload_file(&key, &info, &contents, None)?;
+        Ok(AttachmentUpload { key, outcome })
+    }
+
+    /// Uploads `snapshot` as a ZIP archive to an existing `imported_url`
+    /// attachment item.
+    ///
+    /// `previous_md5` is the hash of the snapshot's current main file, or
+    /// `None` if the item has no file yet.
+    pub fn upload_snapshot(
+        &self,
+        item_key: &str,
+        snapshot: &Snapshot,
+        previous_md5: Option<&str>,
+    ) -> Result<UploadOutcome, ZoteroError> {
+        let info = snapshot.file_info()?;
+        let zip = snapshot.to_zip()?;
+        let response =
+            self.file_request(item_key, &zip_form(item_key, &info, &zip), previous_md5)?;
+        match response.json::<AuthorizationResponse>()? {
+            AuthorizationResponse::Exists { .. } => Ok(UploadOutcome::Exists),
+            AuthorizationResponse::Upload(auth) => {
+                self.complete_upload(item_key, auth, &zip, previous_md5)
+            }
+        }
+    }
+
+    /// Creates an attachment item holding a snapshot of the page at `url` and
+    /// uploads the snapshot.
+    pub fn upload_snapshot_attachment(
+        &self,
+        snapshot: &Snapshot,
+        url: &str,
+        parent_item: Option<&str>,
+    ) -> Result<AttachmentUpload, ZoteroError> {
+        let item = snapshot.attachment_item(url, parent_item)?;
+        let created = self.create_items(&[item], None)?;
+        let key = created_key(&created)?;
+        let outcome = self.upload_snapshot(&key, snapshot, None)?;
+        Ok(AttachmentUpload { key, outcome })
+    }
+
+    pub fn get_items_in_batch(&self, since: usize, batch_size: usize) -> ZoteroItemsBatcher<'_> {
         ZoteroItemsBatcher::new(self, since, batch_size, false)
     }
 
@@ -381,11 +1332,11 @@ impl Zotero {
         &self,
         since: usize,
         batch_size: usize,
-    ) -> ZoteroItemsBatcher {
+    ) -> ZoteroItemsBatcher<'_> {
         ZoteroItemsBatcher::new(self, since, batch_size, true)
     }
 
-    pub fn get_collections_in_batch(&self, batch_size: usize) -> ZoteroCollectionBatcher {
+    pub fn get_collections_in_batch(&self, batch_size: usize) -> ZoteroCollectionBatcher<'_> {
         ZoteroCollectionBatcher::new(self, batch_size)
     }
 }
@@ -415,32 +1366,26 @@ impl<'a> ZoteroItemsBatcher<'a> {
             start: 0,
             limit: batch_size,
             items: vec![].into_iter(),
-            trash: trash,
+            trash,
         }
     }
 
     fn fetch_next_batch(&mut self) -> Result<(), ZoteroBatchError> {
         println!("Fetching batch starting at {}", self.start);
+        let query = Query::new()
+            .start(self.start)
+            .since(self.since as i64)
+            .limit(self.limit as u32)
+            .sort(Sort::DateAdded)
+            .direction(Direction::Asc);
         let response = match self.trash {
             true => self
                 .zotero
-                .get_trash(Some(&[
-                    ("start", &self.start.to_string()),
-                    ("since", &self.since.to_string()),
-                    ("limit", &self.limit.to_string()),
-                    ("sort", "dateAdded"),
-                    ("direction", "asc"),
-                ]))
+                .get_trash(Some(&query))
                 .map_err(|e| ZoteroBatchError::FetchError(Box::new(e)))?,
             false => self
                 .zotero
-                .get_items(Some(&[
-                    ("start", &self.start.to_string()),
-                    ("since", &self.since.to_string()),
-                    ("limit", &self.limit.to_string()),
-                    ("sort", "dateAdded"),
-                    ("direction", "asc"),
-                ]))
+                .get_items(Some(&query))
                 .map_err(|e| ZoteroBatchError::FetchError(Box::new(e)))?,
         };
         let items = response.as_array().unwrap_or(&vec![]).clone();
@@ -490,10 +1435,9 @@ impl<'a> ZoteroCollectionBatcher<'a> {
         println!("Fetching collections batch starting at {}", self.start);
         let response = self
             .zotero
-            .get_collections(Some(&[
-                ("start", &self.start.to_string()),
-                ("limit", &self.limit.to_string()),
-            ]))
+            .get_collections(Some(
+                &Query::new().start(self.start).limit(self.limit as u32),
+            ))
             .map_err(|e| ZoteroBatchError::FetchError(Box::new(e)))?;
         let collections = response.as_array().unwrap_or(&vec![]).clone();
         if collections.is_empty() {
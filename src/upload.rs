@@ -0,0 +1,253 @@
+use bzip2::write::BzEncoder;
+use bzip2::Compression;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::errors::ZoteroError;
+use crate::write::WriteResponse;
+
+/// Metadata of a file sent when requesting upload authorization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    pub filename: String,
+    pub content_type: String,
+    pub md5: String,
+    pub filesize: u64,
+    /// Modification time in milliseconds since the Unix epoch.
+    pub mtime: i64,
+}
+
+impl FileInfo {
+    pub fn new(filename: &str, contents: &[u8], mtime: i64) -> Self {
+        FileInfo {
+            filename: filename.to_string(),
+            content_type: content_type_for(filename).to_string(),
+            md5: format!("{:x}", md5::compute(contents)),
+            filesize: contents.len() as u64,
+            mtime,
+        }
+    }
+
+    /// Builds the file info for `contents`, read from `path`.
+    pub fn from_path(path: &Path, contents: &[u8]) -> Result<Self, ZoteroError> {
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                ZoteroError::UploadError(format!("Invalid file name: {}", path.display()))
+            })?;
+        let mtime = std::fs::metadata(path)?
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        Ok(Self::new(filename, contents, mtime))
+    }
+
+    pub(crate) fn form(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("md5", self.md5.clone()),
+            ("filename", self.filename.clone()),
+            ("filesize", self.filesize.to_string()),
+            ("mtime", self.mtime.to_string()),
+        ]
+    }
+
+    /// Item data for a new `imported_file` attachment holding this file.
+    pub fn attachment_item(&self, parent_item: Option<&str>) -> Value {
+        let mut item = json!({
+            "itemType": "attachment",
+            "linkMode": "imported_file",
+            "title": self.filename,
+            "filename": self.filename,
+            "contentType": self.content_type,
+            "tags": [],
+            "relations": {},
+        });
+        if let Some(parent) = parent_item {
+            item["parentItem"] = Value::String(parent.to_string());
+        } else {
+            item["collections"] = json!([]);
+        }
+        item
+    }
+}
+
+/// Result of uploading a file to an attachment item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadOutcome {
+    /// The server already had a file with the same hash; nothing was sent.
+    Exists,
+    Uploaded,
+    /// Only a binary diff against the previous file was sent.
+    Patched,
+}
+
+/// Diff algorithms accepted by the partial upload endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchAlgorithm {
+    Xdelta,
+    Vcdiff,
+    Bsdiff,
+}
+
+impl PatchAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatchAlgorithm::Xdelta => "xdelta",
+            PatchAlgorithm::Vcdiff => "vcdiff",
+            PatchAlgorithm::Bsdiff => "bsdiff",
+        }
+    }
+}
+
+/// Outcome of trying a partial upload, before any fallback to a full upload.
+pub(crate) enum PatchAttempt {
+    Done(UploadOutcome),
+    /// The server's file does not match the previous hash we sent.
+    PreconditionFailed,
+    /// The server refused the patch; the authorization can be used for a full upload.
+    Unsupported(UploadAuthorization),
+}
+
+/// Whether a response to a patch means the server cannot apply patches of
+/// that algorithm, rather than a failure a full upload would also hit.
+pub(crate) fn patch_unsupported(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_REQUEST | StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::NOT_IMPLEMENTED
+    )
+}
+
+/// A newly created attachment item and the result of uploading its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentUpload {
+    pub key: String,
+    pub outcome: UploadOutcome,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UploadAuthorization {
+    pub url: String,
+    pub content_type: String,
+    pub prefix: String,
+    pub suffix: String,
+    pub upload_key: String,
+}
+
+impl UploadAuthorization {
+    /// Wraps `contents` in the prefix and suffix expected by the storage server.
+    pub fn body(&self, contents: &[u8]) -> Vec<u8> {
+        let mut body = Vec::with_capacity(self.prefix.len() + contents.len() + self.suffix.len());
+        body.extend_from_slice(self.prefix.as_bytes());
+        body.extend_from_slice(contents);
+        body.extend_from_slice(self.suffix.as_bytes());
+        body
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AuthorizationResponse {
+    Exists {
+        #[allow(dead_code)]
+        exists: u8,
+    },
+    Upload(UploadAuthorization),
+}
+
+/// Returns the key of the first object created by a write, or the reason it failed.
+pub(crate) fn created_key(response: &WriteResponse) -> Result<String, ZoteroError> {
+    if let Some(key) = response.success.get(&0) {
+        return Ok(key.clone());
+    }
+    let reason = response
+        .failed
+        .get(&0)
+        .map(|f| format!("{}: {}", f.code, f.message))
+        .unwrap_or_else(|| "no item was created".to_string());
+    Err(ZoteroError::UploadError(format!(
+        "Failed to create attachment item: {}",
+        reason
+    )))
+}
+
+/// Computes a patch from `old` to `new` in the `BSDIFF40` format understood by
+/// `bspatch`.
+pub fn bsdiff_patch(old: &[u8], new: &[u8]) -> Result<Vec<u8>, ZoteroError> {
+    let mut raw = Vec::new();
+    bsdiff::diff(old, new, &mut raw)?;
+
+    // bsdiff-rs interleaves each control triple with its diff and extra bytes;
+    // BSDIFF40 stores the three streams as separately compressed blocks.
+    let mut ctrl = Vec::new();
+    let mut diff = Vec::new();
+    let mut extra = Vec::new();
+    let mut pos = 0;
+    while pos + 24 <= raw.len() {
+        let diff_len = offtin(&raw[pos..pos + 8]) as usize;
+        let extra_len = offtin(&raw[pos + 8..pos + 16]) as usize;
+        ctrl.extend_from_slice(&raw[pos..pos + 24]);
+        pos += 24;
+        diff.extend_from_slice(&raw[pos..pos + diff_len]);
+        pos += diff_len;
+        extra.extend_from_slice(&raw[pos..pos + extra_len]);
+        pos += extra_len;
+    }
+
+    let ctrl = bzip(&ctrl)?;
+    let diff = bzip(&diff)?;
+    let extra = bzip(&extra)?;
+    let mut patch = Vec::with_capacity(32 + ctrl.len() + diff.len() + extra.len());
+    patch.extend_from_slice(b"BSDIFF40");
+    patch.extend_from_slice(&(ctrl.len() as u64).to_le_bytes());
+    patch.extend_from_slice(&(diff.len() as u64).to_le_bytes());
+    patch.extend_from_slice(&(new.len() as u64).to_le_bytes());
+    patch.extend_from_slice(&ctrl);
+    patch.extend_from_slice(&diff);
+    patch.extend_from_slice(&extra);
+    Ok(patch)
+}
+
+fn offtin(buf: &[u8]) -> i64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(buf);
+    let value = u64::from_le_bytes(bytes);
+    let magnitude = (value & !(1u64 << 63)) as i64;
+    if value & (1u64 << 63) != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn bzip(data: &[u8]) -> Result<Vec<u8>, ZoteroError> {
+    let mut encoder = BzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn content_type_for(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "epub" => "application/epub+zip",
+        "html" | "htm" => "text/html",
+        "txt" => "text/plain",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "zip" => "application/zip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+}
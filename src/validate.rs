@@ -0,0 +1,211 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::errors::ZoteroError;
+
+/// Fields every item may have, which `itemTypeFields` does not list.
+const COMMON_FIELDS: [&str; 12] = [
+    "key",
+    "version",
+    "itemType",
+    "creators",
+    "tags",
+    "collections",
+    "relations",
+    "parentItem",
+    "deleted",
+    "inPublications",
+    "dateAdded",
+    "dateModified",
+];
+
+/// Fields of notes and attachments, which `itemTypeFields` does not list.
+const ATTACHMENT_FIELDS: [&str; 9] = [
+    "note",
+    "linkMode",
+    "contentType",
+    "charset",
+    "filename",
+    "md5",
+    "mtime",
+    "path",
+    "accessDate",
+];
+
+/// A problem found by [`ItemTypeSchema::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    NotAnObject,
+    MissingItemType,
+    ItemTypeMismatch {
+        expected: String,
+        found: String,
+    },
+    UnknownField(String),
+    InvalidValue {
+        field: String,
+        expected: &'static str,
+    },
+    InvalidCreatorType {
+        index: usize,
+        creator_type: String,
+    },
+    /// A creator needs either `name` or `firstName`/`lastName`, not both.
+    InvalidCreatorName {
+        index: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NotAnObject => write!(f, "item is not a JSON object"),
+            Violation::MissingItemType => write!(f, "itemType is missing"),
+            Violation::ItemTypeMismatch { expected, found } => {
+                write!(f, "itemType is {:?}, expected {:?}", found, expected)
+            }
+            Violation::UnknownField(field) => write!(f, "{:?} is not a valid field", field),
+            Violation::InvalidValue { field, expected } => {
+                write!(f, "{:?} must be {}", field, expected)
+            }
+            Violation::InvalidCreatorType {
+                index,
+                creator_type,
+            } => write!(
+                f,
+                "creator {} has invalid creatorType {:?}",
+                index, creator_type
+            ),
+            Violation::InvalidCreatorName { index } => write!(
+                f,
+                "creator {} needs either name or firstName/lastName",
+                index
+            ),
+        }
+    }
+}
+
+/// Fields and creator types allowed for an item type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemTypeSchema {
+    pub item_type: String,
+    pub fields: BTreeSet<String>,
+    pub creator_types: BTreeSet<String>,
+}
+
+impl ItemTypeSchema {
+    /// Builds a schema from the `itemTypeFields` and `itemTypeCreatorTypes`
+    /// responses for `item_type`.
+    pub fn from_responses(item_type: &str, fields: &Value, creator_types: &Value) -> Self {
+        let names = |value: &Value, key: &str| -> BTreeSet<String> {
+            value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry[key].as_str().map(str::to_string))
+                .collect()
+        };
+        ItemTypeSchema {
+            item_type: item_type.to_string(),
+            fields: names(fields, "field"),
+            creator_types: names(creator_types, "creatorType"),
+        }
+    }
+
+    fn allows_field(&self, field: &str) -> bool {
+        self.fields.contains(field)
+            || COMMON_FIELDS.contains(&field)
+            || (matches!(self.item_type.as_str(), "note" | "attachment")
+                && ATTACHMENT_FIELDS.contains(&field))
+    }
+
+    /// Checks an item, such as one built from [`get_item_template`], against
+    /// the schema and returns every violation found.
+    ///
+    /// [`get_item_template`]: crate::Zotero::get_item_template
+    pub fn validate(&self, item: &Value) -> Vec<Violation> {
+        let Some(item) = item.as_object() else {
+            return vec![Violation::NotAnObject];
+        };
+        let mut violations = Vec::new();
+        match item.get("itemType").and_then(Value::as_str) {
+            None => violations.push(Violation::MissingItemType),
+            Some(found) if found != self.item_type => {
+                violations.push(Violation::ItemTypeMismatch {
+                    expected: self.item_type.clone(),
+                    found: found.to_string(),
+                })
+            }
+            Some(_) => {}
+        }
+
+        for (field, value) in item {
+            if !self.allows_field(field) {
+                violations.push(Violation::UnknownField(field.clone()));
+                continue;
+            }
+            let expected = match field.as_str() {
+                "creators" => {
+                    self.validate_creators(value, &mut violations);
+                    continue;
+                }
+                "tags" => value
+                    .as_array()
+                    .filter(|tags| tags.iter().all(|tag| tag["tag"].is_string()))
+                    .map_or(Some("a list of {\"tag\": ...} objects"), |_| None),
+                "collections" => value
+                    .as_array()
+                    .filter(|keys| keys.iter().all(Value::is_string))
+                    .map_or(Some("a list of collection keys"), |_| None),
+                "relations" => (!value.is_object()).then_some("an object"),
+                "version" | "mtime" => (!value.is_i64()).then_some("an integer"),
+                "deleted" | "inPublications" => {
+                    (!value.is_boolean() && !value.is_i64()).then_some("a boolean")
+                }
+                _ => (!value.is_string()).then_some("a string"),
+            };
+            if let Some(expected) = expected {
+                violations.push(Violation::InvalidValue {
+                    field: field.clone(),
+                    expected,
+                });
+            }
+        }
+        violations
+    }
+
+    fn validate_creators(&self, creators: &Value, violations: &mut Vec<Violation>) {
+        let Some(creators) = creators.as_array() else {
+            violations.push(Violation::InvalidValue {
+                field: "creators".to_string(),
+                expected: "a list of creators",
+            });
+            return;
+        };
+        for (index, creator) in creators.iter().enumerate() {
+            let creator_type = creator["creatorType"].as_str().unwrap_or_default();
+            if !self.creator_types.contains(creator_type) {
+                violations.push(Violation::InvalidCreatorType {
+                    index,
+                    creator_type: creator_type.to_string(),
+                });
+            }
+            let has_name = creator["name"].is_string();
+            let has_split = creator["firstName"].is_string() || creator["lastName"].is_string();
+            if has_name == has_split {
+                violations.push(Violation::InvalidCreatorName { index });
+            }
+        }
+    }
+
+    /// Like [`validate`](Self::validate), but as a single error listing
+    /// every violation.
+    pub fn check(&self, item: &Value) -> Result<(), ZoteroError> {
+        let violations = self.validate(item);
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(ZoteroError::InvalidItem(violations)),
+        }
+    }
+}
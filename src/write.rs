@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::errors::ZoteroError;
+
+/// Header that makes the server process a create request only once, so that
+/// it can be retried safely.
+pub(crate) const WRITE_TOKEN: &str = "Zotero-Write-Token";
+
+/// A random 32-character token for the `Zotero-Write-Token` header.
+pub(crate) fn write_token() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", random(), random())
+}
+
+/// Per-object report returned by the Zotero API for multi-object writes.
+///
+/// Keys of each map are the indices of the objects in the submitted array.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WriteResponse {
+    #[serde(default)]
+    pub successful: BTreeMap<usize, Value>,
+    #[serde(default)]
+    pub success: BTreeMap<usize, String>,
+    #[serde(default)]
+    pub unchanged: BTreeMap<usize, String>,
+    #[serde(default)]
+    pub failed: BTreeMap<usize, WriteFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteFailure {
+    #[serde(default)]
+    pub key: Option<String>,
+    pub code: u16,
+    pub message: String,
+}
+
+impl WriteResponse {
+    /// Returns `true` if no object in the request failed.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+pub(crate) fn last_modified_version(headers: &reqwest::header::HeaderMap) -> Option<i64> {
+    headers
+        .get("last-modified-version")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok())
+}
+
+pub(crate) fn item_collections(item: &Value) -> Result<(Vec<String>, i64), ZoteroError> {
+    let version = item["version"]
+        .as_i64()
+        .ok_or_else(|| ZoteroError::WriteError("Item has no version".to_string()))?;
+    let collections = item["data"]["collections"]
+        .as_array()
+        .map(|cols| {
+            cols.iter()
+                .filter_map(|c| c.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((collections, version))
+}
@@ -1,10 +1,17 @@
 #[cfg(test)]
 mod mock_tests {
+    use futures::TryStreamExt;
     use httpmock::prelude::*;
     use std::fs;
-    use tokio;
+    use std::time::Duration;
+    use zotero_rs::query::{Direction, Query, Sort};
+    use zotero_rs::sync::{MemoryStore, ObjectKind, SyncStore};
     use zotero_rs::Error;
+    use zotero_rs::Listing;
     use zotero_rs::ZoteroAsync as Zotero;
+    use zotero_rs::{
+        AttachmentFile, FileInfo, RateLimiter, RetryPolicy, SearchOperator, UploadOutcome,
+    };
 
     #[tokio::test]
     async fn test_get_items() {
@@ -12,12 +19,12 @@ mod mock_tests {
         let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
             .expect("Failed to read items_doc.json");
         let mock = server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&items_doc);
         });
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let items = zot.get_items(None).await.unwrap();
         println!("{:?}", items);
@@ -30,12 +37,12 @@ mod mock_tests {
         let item_doc = std::fs::read_to_string("tests/api_responses/item_doc.json")
             .expect("Failed to read item_doc.json");
         server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&item_doc);
         });
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let items_data = zot.get_items(None).await.unwrap();
         let key = items_data["data"]["key"].as_str().unwrap();
@@ -57,14 +64,14 @@ mod mock_tests {
             .expect("Failed to read item_doc.json");
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/users/myuserID/items")
+                .path("/users/475425/items")
                 .query_param("locale", "en-US");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&item_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_locale("en-US");
         zot.set_endpoint(&server.base_url());
         let items_data = zot.get_items(None).await.unwrap();
@@ -81,14 +88,14 @@ mod mock_tests {
             .expect("Failed to read item_doc.json");
 
         server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(429)
                 .header("content-type", "application/json")
                 .header("backoff", "0.2")
                 .body(&item_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let future = zot.get_items(None);
 
@@ -103,13 +110,13 @@ mod mock_tests {
         let file_content =
             fs::read("tests/api_responses/item_file.pdf").expect("Failed to read item_file.pdf");
         let mock = server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items/MYITEMID/file");
+            when.method(GET).path("/users/475425/items/MYITEMID/file");
             then.status(200)
                 .header("content-type", "application/pdf")
                 .body(file_content.clone());
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let file_data = zot.get_file("MYITEMID", None).await.unwrap();
         assert_eq!(file_data, file_content);
@@ -130,7 +137,7 @@ mod mock_tests {
             .expect("Failed to read items_doc.json");
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/users/myuserID/items")
+                .path("/users/475425/items")
                 .query_param("limit", "1");
             then.status(200)
                 .header("content-type", "application/json")
@@ -138,10 +145,1038 @@ mod mock_tests {
                 .body(&items_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let version = zot.get_last_modified_version(None).await.unwrap();
         assert_eq!(version, 12345);
         mock.assert();
     }
+
+    #[tokio::test]
+    async fn test_create_items() {
+        let server = MockServer::start();
+        let creation_doc = fs::read_to_string("tests/api_responses/creation_doc.json")
+            .expect("Failed to read creation_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .header("If-Unmodified-Since-Version", "10")
+                .json_body(serde_json::json!([{"itemType": "book", "title": "Test"}]));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&creation_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let item = serde_json::json!({"itemType": "book", "title": "Test"});
+        let resp = zot.create_items(&[item], Some(10)).await.unwrap();
+        assert!(resp.is_success());
+        assert_eq!(resp.success.get(&0).map(String::as_str), Some("ABC123"));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_update_item() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ABC123")
+                .header("If-Unmodified-Since-Version", "5")
+                .json_body(serde_json::json!({"title": "New title"}));
+            then.status(204).header("last-modified-version", "6");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let version = zot
+            .update_item("ABC123", &serde_json::json!({"title": "New title"}), 5)
+            .await
+            .unwrap();
+        assert_eq!(version, 6);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/MISSING");
+            then.status(404)
+                .header("content-type", "text/html")
+                .body("Item not found");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let error = zot.get_item("MISSING", None).await.unwrap_err();
+        let details = error.api_error().unwrap().clone();
+        assert!(matches!(error, Error::NotFound(_)));
+        assert_eq!(details.status, 404);
+        assert!(details
+            .url
+            .starts_with(&server.url("/users/475425/items/MISSING")));
+        assert_eq!(details.body, "Item not found");
+    }
+
+    #[tokio::test]
+    async fn test_delete_items_precondition_failed() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/users/475425/items")
+                .query_param("itemKey", "ABC123,DEF456")
+                .header("If-Unmodified-Since-Version", "5");
+            then.status(412)
+                .body("Library has been modified since version 5");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let result = zot.delete_items(&["ABC123", "DEF456"], 5).await;
+        match result {
+            Err(Error::PreconditionFailed(error)) => {
+                assert_eq!(error.status, 412);
+                assert_eq!(error.body, "Library has been modified since version 5");
+            }
+            other => panic!("expected PreconditionFailed, got {:?}", other),
+        }
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_update_collection_parent() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/users/475425/collections/KIMI8BSG")
+                .header("If-Unmodified-Since-Version", "6")
+                .json_body(serde_json::json!({
+                    "key": "KIMI8BSG",
+                    "version": 6,
+                    "name": "LoC",
+                    "parentCollection": "N7W92H48"
+                }));
+            then.status(204).header("last-modified-version", "7");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let data = serde_json::json!({
+            "key": "KIMI8BSG",
+            "version": 6,
+            "name": "LoC",
+            "parentCollection": "N7W92H48"
+        });
+        let version = zot.update_collection("KIMI8BSG", &data, 6).await.unwrap();
+        assert_eq!(version, 7);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_add_to_collection() {
+        let server = MockServer::start();
+        let item_doc = fs::read_to_string("tests/api_responses/item_doc.json")
+            .expect("Failed to read item_doc.json");
+        let get_mock = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/X42A7DEE");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&item_doc);
+        });
+        let patch_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/X42A7DEE")
+                .header("If-Unmodified-Since-Version", "1")
+                .json_body(serde_json::json!({
+                    "collections": ["BX9965IJ", "9KH9TNSJ", "KIMI8BSG"]
+                }));
+            then.status(204).header("last-modified-version", "2");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let version = zot.add_to_collection("X42A7DEE", "KIMI8BSG").await.unwrap();
+        assert_eq!(version, 2);
+        get_mock.assert();
+        patch_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_items_typed() {
+        let server = MockServer::start();
+        let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
+            .expect("Failed to read items_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&items_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let items = zot.get_items_typed(None).await.unwrap();
+        assert!(items.iter().all(|item| !item.key.is_empty()));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_query_params() {
+        let server = MockServer::start();
+        let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
+            .expect("Failed to read items_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("limit", "10")
+                .query_param("sort", "dateModified")
+                .query_param("direction", "desc")
+                .query_param("itemType", "-attachment")
+                .query_param("tag", "a || b")
+                .query_param("tag", "-c");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&items_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new()
+            .limit(10)
+            .sort(Sort::DateModified)
+            .direction(Direction::Desc)
+            .not_item_type("attachment")
+            .tag_any(&["a", "b"])
+            .not_tag("c");
+        zot.get_items(Some(&query)).await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_everything_follows_next_links() {
+        let server = MockServer::start();
+        let base = server.base_url();
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/collections")
+                .query_param("start", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("total-results", "3")
+                .header("last-modified-version", "42")
+                .header(
+                    "link",
+                    format!(
+                        "<{0}/users/475425/collections?limit=2&start=2>; rel=\"next\", \
+                         <{0}/users/475425/collections?limit=2&start=2>; rel=\"last\"",
+                        base
+                    ),
+                )
+                .body(r#"[{"key": "A"}, {"key": "B"}]"#);
+        });
+        let second = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/collections")
+                .query_param("start", "2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("total-results", "3")
+                .body(r#"[{"key": "C"}]"#);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().start(0).limit(2);
+        let page = zot
+            .get_page(&Listing::Collections, Some(&query))
+            .await
+            .unwrap();
+        assert_eq!(page.total_results, Some(3));
+        assert_eq!(page.last_modified_version, Some(42));
+        assert!(page.has_next());
+        assert_eq!(page.links.last, page.links.next);
+
+        let all = zot
+            .everything(&Listing::Collections, Some(&query))
+            .await
+            .unwrap();
+        let keys: Vec<_> = all.iter().map(|c| c["key"].as_str().unwrap()).collect();
+        assert_eq!(keys, vec!["A", "B", "C"]);
+        first.assert_hits(2);
+        second.assert();
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_prefetch() {
+        let server = MockServer::start();
+        let mocks: Vec<_> = [
+            ("0", r#"[{"key": "A"}, {"key": "B"}]"#),
+            ("2", r#"[{"key": "C"}, {"key": "D"}]"#),
+            ("4", r#"[{"key": "E"}]"#),
+        ]
+        .iter()
+        .map(|(start, body)| {
+            server.mock(|when, then| {
+                when.method(GET)
+                    .path("/users/475425/items")
+                    .query_param("start", *start)
+                    .query_param("limit", "2");
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .header("total-results", "5")
+                    .body(*body);
+            })
+        })
+        .collect();
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().start(0).limit(2);
+        let items: Vec<_> = zot
+            .stream_items(Some(&query), 2)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let keys: Vec<_> = items.iter().map(|i| i["key"].as_str().unwrap()).collect();
+        assert_eq!(keys, vec!["A", "B", "C", "D", "E"]);
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_prefetch_caps_page_size() {
+        let server = MockServer::start();
+        let mocks: Vec<_> = [(0, 100), (100, 100), (200, 50)]
+            .iter()
+            .map(|&(start, count)| {
+                let body: Vec<_> = (start..start + count)
+                    .map(|n| serde_json::json!({"key": format!("K{}", n)}))
+                    .collect();
+                server.mock(|when, then| {
+                    when.method(GET)
+                        .path("/users/475425/items")
+                        .query_param("start", start.to_string())
+                        .query_param("limit", "150");
+                    then.status(200)
+                        .header("content-type", "application/json")
+                        .header("total-results", "250")
+                        .json_body(serde_json::Value::Array(body));
+                })
+            })
+            .collect();
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().start(0).limit(150);
+        let items: Vec<_> = zot
+            .stream_items(Some(&query), 3)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 250);
+        assert_eq!(items[249]["key"], "K249");
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_tags_follows_next_links() {
+        let server = MockServer::start();
+        let tags_doc = fs::read_to_string("tests/api_responses/collection_tags.json")
+            .expect("Failed to read collection_tags.json");
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/tags")
+                .query_param("start", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header(
+                    "link",
+                    format!(
+                        "<{}/users/475425/tags?start=3>; rel=\"next\"",
+                        server.base_url()
+                    ),
+                )
+                .body(&tags_doc);
+        });
+        let second = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/tags")
+                .query_param("start", "3");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("[]");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().start(0);
+        let tags: Vec<_> = zot
+            .stream_tags(Some(&query), 4)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(tags.len(), 3);
+        first.assert();
+        second.assert();
+    }
+
+    fn mock_json<'a>(
+        server: &'a MockServer,
+        path: &str,
+        query: (&str, &str),
+        version: &str,
+        body: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/475425/{}", path))
+                .query_param(query.0, query.1);
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("last-modified-version", version)
+                .body(body);
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sync_store() {
+        let server = MockServer::start();
+        let mocks = vec![
+            mock_json(
+                &server,
+                "collections",
+                ("format", "versions"),
+                "10",
+                r#"{"C1": 5}"#,
+            ),
+            mock_json(&server, "searches", ("format", "versions"), "10", "{}"),
+            mock_json(
+                &server,
+                "items",
+                ("format", "versions"),
+                "10",
+                r#"{"I1": 7}"#,
+            ),
+            mock_json(
+                &server,
+                "collections",
+                ("collectionKey", "C1"),
+                "10",
+                r#"[{"key": "C1", "version": 5, "data": {"name": "New"}}]"#,
+            ),
+            mock_json(
+                &server,
+                "items",
+                ("itemKey", "I1"),
+                "10",
+                r#"[{"key": "I1", "version": 7, "data": {"itemType": "book"}}]"#,
+            ),
+            mock_json(
+                &server,
+                "deleted",
+                ("since", "3"),
+                "10",
+                r#"{"items": ["I0"]}"#,
+            ),
+        ];
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let mut store = MemoryStore::default();
+        store
+            .upsert(ObjectKind::Item, &[serde_json::json!({"key": "I0"})])
+            .unwrap();
+        store.set_library_version(3).unwrap();
+
+        let version = zot.sync_store(&mut store).await.unwrap();
+        assert_eq!(version, 10);
+        assert_eq!(store.library_version().unwrap(), 10);
+        assert!(store.get(ObjectKind::Item, "I0").is_none());
+        assert_eq!(store.get(ObjectKind::Item, "I1").unwrap()["version"], 7);
+        assert_eq!(
+            store.get(ObjectKind::Collection, "C1").unwrap()["data"]["name"],
+            "New"
+        );
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_conflict() {
+        let server = MockServer::start();
+        let collections = mock_json(&server, "collections", ("format", "versions"), "10", "{}");
+        mock_json(&server, "searches", ("format", "versions"), "11", "{}");
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let result = zot.get_changes_since(0).await;
+        assert!(matches!(result, Err(Error::SyncConflict(_))));
+        collections.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn test_upload_attachment() {
+        let server = MockServer::start();
+        let path = std::env::temp_dir().join("zotero_rs_async_upload.pdf");
+        fs::write(&path, b"%PDF-1.4 test").unwrap();
+        let md5 = format!("{:x}", md5::compute(b"%PDF-1.4 test"));
+
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .json_body_partial(r#"[{"itemType": "attachment", "parentItem": "PARENT"}]"#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"success": {"0": "ATTACH1"}, "unchanged": {}, "failed": {}}"#);
+        });
+        let authorize = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-None-Match", "*")
+                .x_www_form_urlencoded_tuple("md5", &md5)
+                .x_www_form_urlencoded_tuple("filename", "zotero_rs_async_upload.pdf")
+                .x_www_form_urlencoded_tuple("filesize", "13");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "url": server.url("/s3upload"),
+                    "contentType": "multipart/form-data; boundary=XYZ",
+                    "prefix": "PREFIX",
+                    "suffix": "SUFFIX",
+                    "uploadKey": "UPLOADKEY"
+                }));
+        });
+        let storage = server.mock(|when, then| {
+            when.method(POST)
+                .path("/s3upload")
+                .header("content-type", "multipart/form-data; boundary=XYZ")
+                .body("PREFIX%PDF-1.4 testSUFFIX");
+            then.status(201);
+        });
+        let register = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-None-Match", "*")
+                .x_www_form_urlencoded_tuple("upload", "UPLOADKEY");
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let upload = zot.upload_attachment(&path, Some("PARENT")).await.unwrap();
+        assert_eq!(upload.key, "ATTACH1");
+        assert_eq!(upload.outcome, UploadOutcome::Uploaded);
+        create.assert();
+        authorize.assert();
+        storage.assert();
+        register.assert();
+        fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_exists() {
+        let server = MockServer::start();
+        let authorize = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-Match", "oldmd5");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"exists": 1}"#);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", b"contents", 1_700_000_000_000);
+        let outcome = zot
+            .upload_file("ATTACH1", &info, b"contents", Some("oldmd5"))
+            .await
+            .unwrap();
+        assert_eq!(outcome, UploadOutcome::Exists);
+        authorize.assert();
+    }
+
+    fn mock_patch_authorization<'a>(
+        server: &'a MockServer,
+        previous_md5: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-Match", previous_md5)
+                .x_www_form_urlencoded_tuple("filename", "paper.pdf");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "url": server.url("/s3upload"),
+                    "contentType": "application/pdf",
+                    "prefix": "",
+                    "suffix": "",
+                    "uploadKey": "UPLOADKEY"
+                }));
+        })
+    }
+
+    #[tokio::test]
+    async fn test_update_attachment_file_patch() {
+        let server = MockServer::start();
+        let previous = b"%PDF-1.4 first version of the file";
+        let contents = b"%PDF-1.4 second version of the file";
+        let previous_md5 = format!("{:x}", md5::compute(previous));
+        let authorize = mock_patch_authorization(&server, &previous_md5);
+        let patch = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ATTACH1/file")
+                .query_param("algorithm", "bsdiff")
+                .query_param("upload", "UPLOADKEY")
+                .header("If-Match", &previous_md5);
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", contents, 1_700_000_000_000);
+        let outcome = zot
+            .update_attachment_file("ATTACH1", previous, &info, contents)
+            .await
+            .unwrap();
+        assert_eq!(outcome, UploadOutcome::Patched);
+        authorize.assert();
+        patch.assert();
+    }
+
+    #[tokio::test]
+    async fn test_download_file_to() {
+        let server = MockServer::start();
+        let contents = b"0123456789abcdefghij";
+        let item = serde_json::json!({
+            "key": "ATTACH1",
+            "version": 1,
+            "data": {"md5": format!("{:x}", md5::compute(contents)), "mtime": 1_326_311_686_000i64}
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(item);
+        });
+        let file = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1/file");
+            then.status(200)
+                .header("content-type", "application/pdf")
+                .body(contents);
+        });
+
+        let dir = std::env::temp_dir().join("zotero_rs_async_download");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("paper.pdf");
+        fs::remove_file(dir.join("paper.pdf.part")).ok();
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let size = zot.download_file_to("ATTACH1", &path).await.unwrap();
+        assert_eq!(size, 20);
+        assert_eq!(fs::read(&path).unwrap(), contents);
+
+        let download = zot.download_file_stream("ATTACH1", 0).await.unwrap();
+        assert_eq!(download.start, 0);
+        let chunks: Vec<_> = download.reader.try_collect().await.unwrap();
+        assert_eq!(chunks.concat(), contents);
+        file.assert_hits(2);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_get_attachment_file_plain() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "key": "ATTACH1",
+                    "data": {"linkMode": "imported_file", "contentType": "application/epub+zip"}
+                }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1/file");
+            then.status(200)
+                .header("content-type", "application/epub+zip")
+                .body("PK\x03\x04epub");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let file = zot.get_attachment_file("ATTACH1").await.unwrap();
+        assert!(matches!(file, AttachmentFile::File(_)));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(502);
+        });
+
+        let policy = RetryPolicy::new()
+            .max_attempts(2)
+            .base_delay(Duration::from_millis(1));
+        let mut zot = Zotero::user_lib("475425", "myuserkey")
+            .unwrap()
+            .with_retry_policy(policy);
+        zot.set_endpoint(&server.base_url());
+
+        let result = zot.get_items(None).await;
+        assert!(matches!(result, Err(Error::ServerError(_))));
+        mock.assert_hits(2);
+
+        zot.set_retry_policy(RetryPolicy::none());
+        let result = zot.get_items(None).await;
+        assert!(matches!(result, Err(Error::ServerError(_))));
+        mock.assert_hits(3);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_header_delays_later_requests() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("backoff", "0.3")
+                .body("[]");
+        });
+        let item = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ABC123");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let clone = zot.clone();
+        let start = std::time::Instant::now();
+        zot.get_items(None).await.unwrap();
+        let task = tokio::spawn(async move { clone.get_item("ABC123", None).await.unwrap() });
+        task.await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        item.assert();
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_shared_by_clones() {
+        let server = MockServer::start();
+        let item = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ABC123");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey")
+            .unwrap()
+            .with_rate_limiter(RateLimiter::new(10.0, 2));
+        zot.set_endpoint(&server.base_url());
+        let start = std::time::Instant::now();
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let zot = zot.clone();
+                tokio::spawn(async move { zot.get_item("ABC123", None).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        item.assert_hits(5);
+    }
+
+    #[tokio::test]
+    async fn test_builder_custom_client() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items/ABC123")
+                .header("x-custom", "1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-custom", reqwest::header::HeaderValue::from_static("1"));
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+        let zot = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint(&server.base_url())
+            .no_locale()
+            .http_client(client)
+            .build_async()
+            .unwrap();
+        zot.get_item("ABC123", None).await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_groups() {
+        let server = MockServer::start();
+        let groups_doc = fs::read_to_string("tests/api_responses/groups_doc.json").unwrap();
+        let groups: Vec<serde_json::Value> = serde_json::from_str(&groups_doc).unwrap();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/groups");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&groups_doc);
+        });
+        let group = server.mock(|when, then| {
+            when.method(GET).path("/groups/169947");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(groups[0].clone());
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let typed = zot.get_groups_typed(None).await.unwrap();
+        assert_eq!(typed[0].data.name, "smart_cities");
+        let single = zot.get_group(169947).await.unwrap();
+        assert_eq!(single["data"]["name"], "smart_cities");
+        group.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_searches() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/searches");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!([{
+                    "key": "HHF7BB4C",
+                    "version": 7,
+                    "data": {
+                        "key": "HHF7BB4C",
+                        "version": 7,
+                        "name": "Recent",
+                        "conditions": [
+                            {"condition": "dateAdded", "operator": "isInTheLast", "value": "7 days"}
+                        ]
+                    }
+                }]));
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let searches = zot.get_searches_typed(None).await.unwrap();
+        assert_eq!(searches[0].data.name, "Recent");
+        assert_eq!(
+            searches[0].data.conditions[0].operator,
+            SearchOperator::IsInTheLast
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_items_csljson() {
+        let server = MockServer::start();
+        let next = format!(
+            "<{}/users/475425/items?format=csljson&limit=1&start=1>; rel=\"next\"",
+            server.base_url()
+        );
+        let second = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "csljson")
+                .query_param("start", "1");
+            then.status(200)
+                .header("content-type", "application/vnd.citationstyles.csl+json")
+                .body(r#"{"items": [{"id": "475425/B", "type": "report"}]}"#);
+        });
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "csljson");
+            then.status(200)
+                .header("content-type", "application/vnd.citationstyles.csl+json")
+                .header("link", next)
+                .body(r#"{"items": [{"id": "475425/A", "type": "book"}]}"#);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().limit(1);
+        let csl = zot.get_items_csljson(Some(&query)).await.unwrap();
+        let ids: Vec<_> = csl["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["475425/A", "475425/B"]);
+        first.assert_hits(1);
+        second.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_citations() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "json")
+                .query_param("include", "citation,bib")
+                .query_param("style", "chicago-author-date")
+                .query_param("locale", "en-US");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!([{
+                    "key": "U52JBZ4X",
+                    "version": 1,
+                    "citation": "<span>(Smith 2014)</span>",
+                    "bib": "<div class=\"csl-bib-body\"><div class=\"csl-entry\">Smith, Anna. 2014.</div></div>"
+                }]));
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let citations = zot
+            .get_citations("chicago-author-date", None, None)
+            .await
+            .unwrap();
+        assert_eq!(citations[0].key, "U52JBZ4X");
+        assert_eq!(
+            citations[0].citation_text().as_deref(),
+            Some("(Smith 2014)")
+        );
+        assert_eq!(
+            citations[0].bib_text().as_deref(),
+            Some("Smith, Anna. 2014.")
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_feed() {
+        let server = MockServer::start();
+        let atom = fs::read_to_string("tests/api_responses/items_atom.xml")
+            .expect("Failed to read items_atom.xml");
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/collections/BX9965IJ/items")
+                .query_param("format", "atom");
+            then.status(200)
+                .header("content-type", "application/atom+xml; charset=UTF-8")
+                .body(&atom);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let listing = Listing::CollectionItems("BX9965IJ".to_string());
+        let feed = zot.get_feed(&listing, None).await.unwrap();
+        assert_eq!(feed.total_results, Some(5));
+        assert_eq!(feed.entries[0].version, Some(1));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_item_template() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/items/new")
+                .query_param("itemType", "attachment")
+                .query_param("linkMode", "linked_url");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "itemType": "attachment",
+                    "linkMode": "linked_url",
+                    "title": "",
+                    "accessDate": "",
+                    "url": "",
+                    "note": "",
+                    "tags": [],
+                    "relations": {},
+                    "contentType": "",
+                    "charset": ""
+                }));
+        });
+        let schema = server.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(fs::read_to_string("tests/api_responses/schema.json").unwrap());
+        });
+
+        let types = server.mock(|when, then| {
+            when.method(GET).path("/itemTypes");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(fs::read_to_string("tests/api_responses/item_types.json").unwrap());
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        zot.set_schema_cache(None);
+        let template = zot
+            .get_item_template("attachment", Some("linked_url"))
+            .await
+            .unwrap();
+        assert_eq!(template["linkMode"], "linked_url");
+        let item_types = zot.get_item_types().await.unwrap();
+        assert_eq!(item_types[0]["itemType"], "artwork");
+        let mut item = template.clone();
+        item["title"] = serde_json::json!("Example");
+        zot.validate_item(&item).await.unwrap();
+        mock.assert();
+        types.assert();
+        schema.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_schema_cached() {
+        let server = MockServer::start();
+        let path = std::env::temp_dir().join("zotero_rs_async_schema.json");
+        let _ = fs::remove_file(&path);
+        let schema_doc = fs::read_to_string("tests/api_responses/schema.json").unwrap();
+        let fetched = server.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("ETag", "\"schema-29\"")
+                .body(&schema_doc);
+        });
+
+        let zot = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint(&server.base_url())
+            .schema_cache(&path)
+            .build_async()
+            .unwrap();
+        let schema = zot.get_schema().await.unwrap();
+        assert!(path.exists());
+        fetched.assert();
+
+        // Without a connection, the cached schema is read from disk.
+        let offline = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint("http://127.0.0.1:1")
+            .retry_policy(RetryPolicy::none())
+            .schema_cache(&path)
+            .build_async()
+            .unwrap();
+        assert_eq!(offline.get_schema().await.unwrap(), schema);
+        let fields = offline.get_item_type_fields("book").await.unwrap();
+        assert_eq!(fields[0]["field"], "title");
+        fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod atom_tests {
+    use std::fs;
+    use zotero_rs::atom::{Content, Entry, Feed};
+    use zotero_rs::Error;
+
+    #[test]
+    fn test_items_feed() {
+        let doc = fs::read_to_string("tests/api_responses/items_atom.xml")
+            .expect("Failed to read items_atom.xml");
+        let feed = Feed::from_xml(&doc).unwrap();
+        assert_eq!(feed.title, "Zotero / Z public library / Items");
+        assert_eq!(feed.total_results, Some(5));
+        assert!(feed.has_next());
+        assert_eq!(
+            feed.links.next.as_ref().unwrap().as_str(),
+            "https://api.zotero.org/users/475425/items?content=json&format=atom&limit=2&start=2"
+        );
+        assert_eq!(feed.entries.len(), 2);
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "http://zotero.org/users/475425/items/U52JBZ4X");
+        assert_eq!(entry.key.as_deref(), Some("U52JBZ4X"));
+        assert_eq!(entry.version, Some(1));
+        assert_eq!(entry.item_type.as_deref(), Some("journalArticle"));
+        assert_eq!(entry.author.as_ref().unwrap().name, "Z public library");
+        assert_eq!(entry.content_type.as_deref(), Some("json"));
+        match entry.content {
+            Some(Content::Json(ref data)) => {
+                assert_eq!(data["title"], "Front Matter");
+                assert_eq!(data["tags"][0]["tag"], "Physics & Society");
+            }
+            ref other => panic!("expected JSON content, got {:?}", other),
+        }
+        assert_eq!(
+            feed.entries[1].links.up.as_ref().unwrap().href,
+            "https://api.zotero.org/users/475425/items/47RUN6RI?content=json"
+        );
+    }
+
+    #[test]
+    fn test_citation_entry() {
+        let doc = fs::read_to_string("tests/api_responses/citation_doc.xml")
+            .expect("Failed to read citation_doc.xml");
+        let entry = Entry::from_xml(&doc).unwrap();
+        assert_eq!(entry.title, "Front Matter");
+        assert_eq!(entry.published.as_deref(), Some("2011-01-13T02:32:38Z"));
+        assert_eq!(entry.content_type.as_deref(), Some("citation"));
+        match entry.content {
+            Some(Content::Html(ref html)) => {
+                assert!(html.starts_with("<span xmlns=\"http://www.w3.org/1999/xhtml\">"));
+                assert!(html.ends_with("</span>"));
+            }
+            ref other => panic!("expected HTML content, got {:?}", other),
+        }
+        assert_eq!(
+            entry.links.alternate.unwrap().content_type.as_deref(),
+            Some("text/html")
+        );
+
+        assert!(matches!(Feed::from_xml(&doc), Err(Error::AtomError(_))));
+        assert!(matches!(Feed::from_xml("<feed"), Err(Error::XmlError(_))));
+    }
+}
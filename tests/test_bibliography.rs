@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod bibliography_tests {
+    use serde_json::json;
+    use zotero_rs::bibliography::html_to_text;
+    use zotero_rs::{Bibliography, FormattedItem};
+
+    #[test]
+    fn test_numbered_bibliography() {
+        let html = concat!(
+            "<div class=\"csl-bib-body\" style=\"line-height: 1.35; \">\n",
+            "  <div class=\"csl-entry\" style=\"clear: left; \">\n",
+            "    <div class=\"csl-left-margin\" style=\"float: left;\">[1]</div>",
+            "<div class=\"csl-right-inline\">A. Smith, <i>Cities &amp; Data</i>, 2014.</div>\n",
+            "  </div>\n",
+            "  <div class=\"csl-entry\" style=\"clear: left; \">\n",
+            "    <div class=\"csl-left-margin\" style=\"float: left;\">[2]</div>",
+            "<div class=\"csl-right-inline\">B. Jones, \u{201c}Streets,\u{201d} ",
+            "doi: <a href=\"https://doi.org/10.1/x\">10.1/x</a>.</div>\n",
+            "  </div>\n",
+            "</div>"
+        );
+        let bibliography = Bibliography::from_html(html);
+        assert_eq!(bibliography.entries.len(), 2);
+        assert!(bibliography.entries[0].starts_with("<div class=\"csl-entry\""));
+        assert!(bibliography.entries[1].ends_with("</div>\n  </div>"));
+        assert_eq!(
+            bibliography.text(),
+            "[1] A. Smith, Cities & Data, 2014.\n[2] B. Jones, \u{201c}Streets,\u{201d} doi: 10.1/x."
+        );
+    }
+
+    #[test]
+    fn test_formatted_item() {
+        let item = FormattedItem::from_value(&json!({
+            "key": "U52JBZ4X",
+            "version": 1,
+            "citation": "<span>(Ans&#230;lm and Tka&#x10d;ik 2014)</span>",
+            "bib": "<div class=\"csl-bib-body\"><div class=\"csl-entry\">Ans&#230;lm,&nbsp;R. 2014.</div></div>"
+        }));
+        assert_eq!(item.key, "U52JBZ4X");
+        assert_eq!(
+            item.citation_text().as_deref(),
+            Some("(Ans\u{e6}lm and Tka\u{10d}ik 2014)")
+        );
+        assert_eq!(item.bib_text().as_deref(), Some("Ans\u{e6}lm, R. 2014."));
+        assert_eq!(html_to_text("Fish &amp chips <b"), "Fish &amp chips");
+    }
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "mirror")]
+
+mod mirror_tests {
+    use httpmock::prelude::*;
+    use zotero_rs::mirror::Mirror;
+    use zotero_rs::{Library, ObjectKind, SyncStore, Zotero};
+
+    fn mock_json<'a>(
+        server: &'a MockServer,
+        path: &str,
+        query: (&str, &str),
+        body: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/475425/{}", path))
+                .query_param(query.0, query.1);
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("last-modified-version", "10")
+                .body(body);
+        })
+    }
+
+    #[test]
+    fn test_mirror_sync() {
+        let server = MockServer::start();
+        mock_json(
+            &server,
+            "collections",
+            ("format", "versions"),
+            r#"{"C1": 5}"#,
+        );
+        mock_json(&server, "searches", ("format", "versions"), "{}");
+        mock_json(&server, "items", ("format", "versions"), r#"{"I1": 7}"#);
+        mock_json(
+            &server,
+            "collections",
+            ("collectionKey", "C1"),
+            r#"[{"key": "C1", "version": 5, "data": {"name": "Papers", "relations": {}}}]"#,
+        );
+        mock_json(
+            &server,
+            "items",
+            ("itemKey", "I1"),
+            r#"[{"key": "I1", "version": 7, "data": {
+                "itemType": "book",
+                "tags": [{"tag": "rust"}, {"tag": "sqlite", "type": 1}],
+                "collections": ["C1"],
+                "relations": {"dc:replaces": ["http://zotero.org/users/1/items/OLD"]}
+            }}]"#,
+        );
+        mock_json(&server, "deleted", ("since", "0"), "{}");
+        mock_json(&server, "fulltext", ("since", "0"), r#"{"I1": 9}"#);
+        mock_json(
+            &server,
+            "items/I1/fulltext",
+            ("locale", "en-US"),
+            r#"{"content": "Full text", "indexedPages": 1, "totalPages": 1}"#,
+        );
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let mut mirror = Mirror::open_in_memory(Library::User(475425)).unwrap();
+        assert_eq!(mirror.sync(&zot).unwrap(), 10);
+        assert_eq!(mirror.library_version().unwrap(), 10);
+
+        assert_eq!(mirror.item("I1").unwrap().unwrap()["version"], 7);
+        assert_eq!(mirror.collections().unwrap().len(), 1);
+        assert_eq!(mirror.collection_items("C1").unwrap().len(), 1);
+        assert_eq!(mirror.items_with_tag("sqlite").unwrap().len(), 1);
+        assert_eq!(
+            mirror.tags().unwrap(),
+            vec![("rust".to_string(), 1), ("sqlite".to_string(), 1)]
+        );
+        assert_eq!(
+            mirror.relations(ObjectKind::Item, "I1").unwrap(),
+            vec![(
+                "dc:replaces".to_string(),
+                "http://zotero.org/users/1/items/OLD".to_string()
+            )]
+        );
+        assert_eq!(mirror.fulltext("I1").unwrap().as_deref(), Some("Full text"));
+    }
+
+    #[test]
+    fn test_mirror_applies_deletions() {
+        let mut mirror = Mirror::open_in_memory(Library::Group(1)).unwrap();
+        let item = serde_json::json!({
+            "key": "I1",
+            "version": 1,
+            "data": {"itemType": "book", "tags": [{"tag": "a"}], "collections": ["C1"]}
+        });
+        mirror.upsert(ObjectKind::Item, &[item]).unwrap();
+        assert_eq!(mirror.items().unwrap().len(), 1);
+
+        mirror
+            .delete(ObjectKind::Item, &["I1".to_string()])
+            .unwrap();
+        assert!(mirror.item("I1").unwrap().is_none());
+        assert!(mirror.tags().unwrap().is_empty());
+        assert!(mirror.collection_items("C1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mirror_rejects_other_library() {
+        let zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        let mut mirror = Mirror::open_in_memory(Library::Group(1)).unwrap();
+        assert!(matches!(
+            mirror.sync(&zot),
+            Err(zotero_rs::Error::ConfigError(_))
+        ));
+        assert_eq!(mirror.library_version().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_mirror_keeps_memberships_of_updated_collection() {
+        let mut mirror = Mirror::open_in_memory(Library::Group(1)).unwrap();
+        let item = serde_json::json!({
+            "key": "I1",
+            "version": 1,
+            "data": {"itemType": "book", "collections": ["C1"]}
+        });
+        let collection =
+            |name: &str| serde_json::json!({"key": "C1", "version": 2, "data": {"name": name}});
+        mirror.upsert(ObjectKind::Item, &[item]).unwrap();
+        mirror
+            .upsert(ObjectKind::Collection, &[collection("Papers")])
+            .unwrap();
+        mirror
+            .upsert(ObjectKind::Collection, &[collection("Renamed")])
+            .unwrap();
+        assert_eq!(mirror.collection_items("C1").unwrap().len(), 1);
+
+        mirror
+            .delete(ObjectKind::Collection, &["C1".to_string()])
+            .unwrap();
+        assert!(mirror.collection_items("C1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod model_tests {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+    use std::fs;
+    use zotero_rs::{Collection, Group, Item, Schema, Tag};
+
+    fn round_trip<T>(path: &str) -> T
+    where
+        T: DeserializeOwned + Serialize + PartialEq + Debug,
+    {
+        let doc = fs::read_to_string(path).expect("Failed to read fixture");
+        let parsed: T = serde_json::from_str(&doc).unwrap();
+        let value = serde_json::to_value(&parsed).unwrap();
+        let reparsed: T = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, reparsed);
+        parsed
+    }
+
+    #[test]
+    fn test_item_doc() {
+        let item: Item = round_trip("tests/api_responses/item_doc.json");
+        assert_eq!(item.key, "X42A7DEE");
+        assert_eq!(item.data.item_type, "book");
+        assert_eq!(
+            item.data.creators[0].name.as_deref(),
+            Some("Institute of Physics (Great Britain)")
+        );
+        assert_eq!(item.data.collections, vec!["BX9965IJ", "9KH9TNSJ"]);
+        assert_eq!(item.library.unwrap().library_type, "user");
+    }
+
+    #[test]
+    fn test_items_doc() {
+        let items: Vec<Item> = round_trip("tests/api_responses/items_doc.json");
+        assert!(!items.is_empty());
+    }
+
+    #[test]
+    fn test_attachment_doc() {
+        let item: Item = round_trip("tests/api_responses/attachments_doc.json");
+        assert_eq!(item.data.item_type, "attachment");
+        assert_eq!(item.data.parent_item.as_deref(), Some("47RUN6RI"));
+        assert_eq!(
+            item.data.field("md5"),
+            Some("80893b449d89a94722a04190d4330008")
+        );
+        assert!(item.links.enclosure.is_some());
+    }
+
+    #[test]
+    fn test_collections_doc() {
+        let collections: Vec<Collection> = round_trip("tests/api_responses/collections_doc.json");
+        assert_eq!(collections[0].data.name, "LoC");
+        assert_eq!(collections[0].data.parent_collection, None);
+
+        let collection: Collection = round_trip("tests/api_responses/collection_doc.json");
+        assert_eq!(
+            collection.data.parent_collection.as_deref(),
+            Some("T3DAUPWW")
+        );
+        let value = serde_json::to_value(&collections[0]).unwrap();
+        assert_eq!(value["data"]["parentCollection"], false);
+    }
+
+    #[test]
+    fn test_tags_doc() {
+        let tags: Vec<Tag> = round_trip("tests/api_responses/tags_doc.json");
+        assert_eq!(tags[0].tag, "Community / Economic Development");
+        assert_eq!(tags[0].meta.as_ref().unwrap().tag_type, 1);
+
+        let tags: Vec<Tag> = round_trip("tests/api_responses/collection_tags.json");
+        assert_eq!(tags.len(), 3);
+    }
+    #[test]
+    fn test_groups_doc() {
+        let groups: Vec<Group> = round_trip("tests/api_responses/groups_doc.json");
+        assert_eq!(groups[0].id, 169947);
+        assert_eq!(groups[0].data.name, "smart_cities");
+        assert_eq!(groups[0].data.file_editing, "admins");
+        assert_eq!(groups[0].data.members, vec![1177919, 1408658]);
+    }
+
+    #[test]
+    fn test_schema_doc() {
+        let schema: Schema = round_trip("tests/api_responses/schema.json");
+        assert_eq!(schema.version, 29);
+        let artwork = schema.item_type("artwork").unwrap();
+        assert_eq!(artwork.fields[2].base_field.as_deref(), Some("medium"));
+        assert!(artwork.creator_types[0].primary);
+        assert_eq!(schema.locales["de-DE"].item_types["book"], "Buch");
+
+        let book = schema.item_type_schema("book").unwrap();
+        assert!(book.fields.contains("ISBN"));
+        assert!(book.creator_types.contains("translator"));
+        assert!(schema.item_type_schema("dataset").is_none());
+    }
+}
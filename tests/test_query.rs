@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod query_tests {
+    use zotero_rs::query::{Format, Include, QMode, Query};
+
+    #[test]
+    fn test_setters_replace_previous_value() {
+        let query = Query::new()
+            .limit(10)
+            .limit(50)
+            .q("foo")
+            .qmode(QMode::Everything);
+        let pairs: Vec<_> = query.pairs().collect();
+        assert_eq!(
+            pairs,
+            vec![("limit", "50"), ("q", "foo"), ("qmode", "everything")]
+        );
+    }
+
+    #[test]
+    fn test_tags_accumulate() {
+        let query = Query::new().tag("a").tag("b").not_tag("c");
+        let tags: Vec<_> = query.pairs().filter(|(k, _)| *k == "tag").collect();
+        assert_eq!(tags, vec![("tag", "a"), ("tag", "b"), ("tag", "-c")]);
+    }
+
+    #[test]
+    fn test_lists() {
+        let query = Query::new()
+            .item_keys(&["ABC", "DEF"])
+            .include(&[Include::Data, Include::Bib])
+            .format(Format::Versions)
+            .since(42);
+        assert_eq!(query.get("itemKey"), Some("ABC,DEF"));
+        assert_eq!(query.get("include"), Some("data,bib"));
+        assert_eq!(query.get("format"), Some("versions"));
+        assert_eq!(query.get("since"), Some("42"));
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod search_tests {
+    use serde_json::json;
+    use zotero_rs::models::SavedSearchData;
+    use zotero_rs::{Collection, Error, Item, SearchCondition, SearchOperator};
+
+    fn item(key: &str, data: serde_json::Value) -> Item {
+        let mut data = data;
+        data["key"] = json!(key);
+        serde_json::from_value(json!({"key": key, "version": 1, "data": data})).unwrap()
+    }
+
+    fn items() -> Vec<Item> {
+        vec![
+            item(
+                "AAAA0001",
+                json!({
+                    "itemType": "book",
+                    "title": "Climate Models",
+                    "creators": [{"creatorType": "author", "firstName": "Ada", "lastName": "Lovelace"}],
+                    "tags": [{"tag": "Climate"}],
+                    "collections": ["PARENT01"],
+                    "date": "2012-05-01",
+                    "numPages": "320"
+                }),
+            ),
+            item(
+                "AAAA0002",
+                json!({
+                    "itemType": "journalArticle",
+                    "title": "Ocean currents",
+                    "collections": ["CHILD001"],
+                    "date": "2020",
+                    "numPages": "12"
+                }),
+            ),
+            item(
+                "AAAA0003",
+                json!({
+                    "itemType": "note",
+                    "parentItem": "AAAA0001",
+                    "note": "<p>reading notes</p>"
+                }),
+            ),
+            item(
+                "AAAA0004",
+                json!({"itemType": "book", "title": "Climate in the trash", "deleted": 1}),
+            ),
+        ]
+    }
+
+    fn search(conditions: Vec<SearchCondition>) -> SavedSearchData {
+        SavedSearchData {
+            key: None,
+            version: None,
+            name: "test".to_string(),
+            conditions,
+            other: Default::default(),
+        }
+    }
+
+    fn keys(search: &SavedSearchData, collections: &[Collection]) -> Vec<String> {
+        search
+            .execute(&items(), collections)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.key.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_text_conditions() {
+        let contains = search(vec![SearchCondition::new(
+            "title",
+            SearchOperator::Contains,
+            "climate",
+        )]);
+        assert_eq!(keys(&contains, &[]), vec!["AAAA0001"]);
+
+        let any = search(vec![
+            SearchCondition::new("joinMode", SearchOperator::Other("any".into()), ""),
+            SearchCondition::new("lastName", SearchOperator::Is, "lovelace"),
+            SearchCondition::new("itemType", SearchOperator::Is, "journalArticle"),
+        ]);
+        assert_eq!(keys(&any, &[]), vec!["AAAA0001", "AAAA0002"]);
+
+        let negative = search(vec![
+            SearchCondition::new("noChildren", SearchOperator::Other("true".into()), ""),
+            SearchCondition::new("tag", SearchOperator::IsNot, "climate"),
+        ]);
+        assert_eq!(keys(&negative, &[]), vec!["AAAA0002"]);
+    }
+
+    #[test]
+    fn test_number_and_date_conditions() {
+        let pages = search(vec![SearchCondition::new(
+            "numPages",
+            SearchOperator::IsGreaterThan,
+            "100",
+        )]);
+        assert_eq!(keys(&pages, &[]), vec!["AAAA0001"]);
+
+        let before = search(vec![SearchCondition::new(
+            "date",
+            SearchOperator::IsBefore,
+            "2015-01-01",
+        )]);
+        assert_eq!(keys(&before, &[]), vec!["AAAA0001"]);
+
+        let recent = search(vec![SearchCondition::new(
+            "date",
+            SearchOperator::IsInTheLast,
+            "5 years",
+        )]);
+        assert!(keys(&recent, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_collections_and_children() {
+        let collections: Vec<Collection> = serde_json::from_value(json!([
+            {"key": "PARENT01", "version": 1, "data": {"name": "Parent", "parentCollection": false}},
+            {"key": "CHILD001", "version": 1, "data": {"name": "Child", "parentCollection": "PARENT01"}}
+        ]))
+        .unwrap();
+        let in_parent = SearchCondition::new("collection", SearchOperator::Is, "PARENT01");
+
+        assert_eq!(
+            keys(&search(vec![in_parent.clone()]), &collections),
+            vec!["AAAA0001"]
+        );
+        let recursive = search(vec![
+            SearchCondition::new("recursive", SearchOperator::Other("true".into()), ""),
+            in_parent.clone(),
+        ]);
+        assert_eq!(keys(&recursive, &collections), vec!["AAAA0001", "AAAA0002"]);
+
+        let with_children = search(vec![
+            SearchCondition::new(
+                "includeParentsAndChildren",
+                SearchOperator::Other("true".into()),
+                "",
+            ),
+            in_parent,
+        ]);
+        assert_eq!(
+            keys(&with_children, &collections),
+            vec!["AAAA0001", "AAAA0003"]
+        );
+    }
+
+    #[test]
+    fn test_deleted_items() {
+        let title = SearchCondition::new("title", SearchOperator::BeginsWith, "Climate");
+        assert_eq!(keys(&search(vec![title.clone()]), &[]), vec!["AAAA0001"]);
+
+        let deleted = search(vec![
+            SearchCondition::new("deleted", SearchOperator::Other("true".into()), ""),
+            title,
+        ]);
+        assert_eq!(keys(&deleted, &[]), vec!["AAAA0001", "AAAA0004"]);
+    }
+
+    #[test]
+    fn test_unsupported_condition() {
+        let fulltext = search(vec![SearchCondition::new(
+            "fulltextContent",
+            SearchOperator::Contains,
+            "ocean",
+        )]);
+        match fulltext.execute(&items(), &[]) {
+            Err(Error::UnsupportedSearchCondition(condition)) => {
+                assert_eq!(condition, "fulltextContent")
+            }
+            other => panic!("expected UnsupportedSearchCondition, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_condition_serialization() {
+        let condition: SearchCondition = serde_json::from_value(
+            json!({"condition": "title", "operator": "doesNotContain", "value": "x"}),
+        )
+        .unwrap();
+        assert_eq!(condition.operator, SearchOperator::DoesNotContain);
+        let value = serde_json::to_value(&condition).unwrap();
+        assert_eq!(value["operator"], "doesNotContain");
+    }
+}
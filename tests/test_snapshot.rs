@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod snapshot_tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use zotero_rs::snapshot::is_zipped;
+    use zotero_rs::{Error, Snapshot};
+
+    fn snapshot() -> Snapshot {
+        let mut files = BTreeMap::new();
+        files.insert(
+            "page.html".to_string(),
+            b"<html><img src=\"img/a.png\"></html>".to_vec(),
+        );
+        files.insert("img/a.png".to_string(), b"\x89PNG".to_vec());
+        Snapshot::new(files, "page.html")
+    }
+
+    #[test]
+    fn test_zip_round_trip() {
+        let zip = snapshot().to_zip().unwrap();
+        assert!(zip.starts_with(b"PK\x03\x04"));
+
+        let unpacked = Snapshot::from_zip(&zip, None).unwrap();
+        assert_eq!(unpacked.files, snapshot().files);
+        assert_eq!(unpacked.main_file.as_deref(), Some("page.html"));
+        assert_eq!(
+            unpacked.main_html(),
+            Some(&b"<html><img src=\"img/a.png\"></html>"[..])
+        );
+
+        let dir = std::env::temp_dir().join("zotero_rs_snapshot");
+        fs::remove_dir_all(&dir).ok();
+        let main = unpacked.extract_to(&dir).unwrap().unwrap();
+        assert_eq!(main, dir.join("page.html"));
+        assert_eq!(fs::read(dir.join("img/a.png")).unwrap(), b"\x89PNG");
+        assert_eq!(Snapshot::from_dir(&dir, "page.html").unwrap(), snapshot());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unsafe_paths_are_rejected() {
+        let mut files = BTreeMap::new();
+        files.insert("../page.html".to_string(), b"<html></html>".to_vec());
+        let snapshot = Snapshot::new(files, "../page.html");
+        match snapshot.to_zip() {
+            Err(Error::UnsafePath(name)) => assert_eq!(name, "../page.html"),
+            other => panic!("expected UnsafePath, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_is_zipped() {
+        let zip = snapshot().to_zip().unwrap();
+        let snapshot_item = serde_json::json!({
+            "data": {"linkMode": "imported_url", "contentType": "text/html"}
+        });
+        let epub_item = serde_json::json!({
+            "data": {"linkMode": "imported_file", "contentType": "application/epub+zip"}
+        });
+        let zip_item = serde_json::json!({
+            "data": {"linkMode": "imported_file", "contentType": "application/zip"}
+        });
+        assert!(is_zipped(Some("application/zip"), &snapshot_item, &zip));
+        assert!(is_zipped(None, &snapshot_item, &zip));
+        assert!(!is_zipped(Some("application/zip"), &epub_item, &zip));
+        assert!(!is_zipped(Some("application/zip"), &zip_item, &zip));
+        assert!(!is_zipped(None, &epub_item, &zip));
+        assert!(!is_zipped(
+            Some("text/html"),
+            &snapshot_item,
+            b"<html></html>"
+        ));
+    }
+}
@@ -1,9 +1,18 @@
 #[cfg(test)]
 mod mock_tests {
     use httpmock::prelude::*;
+    use std::collections::BTreeMap;
     use std::fs;
+    use std::time::Duration;
+    use zotero_rs::query::{Direction, Format, Query, Sort};
+    use zotero_rs::sync::{MemoryStore, ObjectKind, SyncStore};
     use zotero_rs::Error;
+    use zotero_rs::Library;
+    use zotero_rs::Listing;
     use zotero_rs::Zotero;
+    use zotero_rs::{
+        AttachmentFile, FileInfo, RateLimiter, RetryPolicy, Snapshot, UploadOutcome, ZoteroBuilder,
+    };
 
     #[test]
     fn test_get_items() {
@@ -11,12 +20,12 @@ mod mock_tests {
         let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
             .expect("Failed to read items_doc.json");
         let mock = server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&items_doc);
         });
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let items = zot.get_items(None).unwrap();
         println!("{:?}", items);
@@ -29,12 +38,12 @@ mod mock_tests {
         let item_doc = std::fs::read_to_string("tests/api_responses/item_doc.json")
             .expect("Failed to read item_doc.json");
         server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&item_doc);
         });
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let items_data = zot.get_items(None).unwrap();
         let key = items_data["data"]["key"].as_str().unwrap();
@@ -56,14 +65,14 @@ mod mock_tests {
             .expect("Failed to read item_doc.json");
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/users/myuserID/items")
+                .path("/users/475425/items")
                 .query_param("locale", "en-US");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(&item_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_locale("en-US");
         zot.set_endpoint(&server.base_url());
         let items_data = zot.get_items(None).unwrap();
@@ -80,14 +89,14 @@ mod mock_tests {
             .expect("Failed to read item_doc.json");
 
         server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items");
+            when.method(GET).path("/users/475425/items");
             then.status(429)
                 .header("content-type", "application/json")
                 .header("backoff", "0.2")
                 .body(&item_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let result = zot.get_items(None);
         // Assert that the error is TooManyRequests
@@ -100,13 +109,13 @@ mod mock_tests {
         let file_content =
             fs::read("tests/api_responses/item_file.pdf").expect("Failed to read item_file.pdf");
         let mock = server.mock(|when, then| {
-            when.method(GET).path("/users/myuserID/items/MYITEMID/file");
+            when.method(GET).path("/users/475425/items/MYITEMID/file");
             then.status(200)
                 .header("content-type", "application/pdf")
                 .body(file_content.clone());
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let file_data = zot.get_file("MYITEMID", None).unwrap();
         assert_eq!(file_data, file_content);
@@ -127,7 +136,7 @@ mod mock_tests {
             .expect("Failed to read items_doc.json");
         let mock = server.mock(|when, then| {
             when.method(GET)
-                .path("/users/myuserID/items")
+                .path("/users/475425/items")
                 .query_param("limit", "1");
             then.status(200)
                 .header("content-type", "application/json")
@@ -135,10 +144,1482 @@ mod mock_tests {
                 .body(&items_doc);
         });
 
-        let mut zot = Zotero::user_lib("myuserID", "myuserkey").unwrap();
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
         zot.set_endpoint(&server.base_url());
         let version = zot.get_last_modified_version(None).unwrap();
         assert_eq!(version, 12345);
         mock.assert();
     }
+
+    #[test]
+    fn test_create_items() {
+        let server = MockServer::start();
+        let creation_doc = fs::read_to_string("tests/api_responses/creation_doc.json")
+            .expect("Failed to read creation_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .header("If-Unmodified-Since-Version", "10")
+                .json_body(serde_json::json!([{"itemType": "book", "title": "Test"}]));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&creation_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let item = serde_json::json!({"itemType": "book", "title": "Test"});
+        let resp = zot.create_items(&[item], Some(10)).unwrap();
+        assert!(resp.is_success());
+        assert_eq!(resp.success.get(&0).map(String::as_str), Some("ABC123"));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_update_item() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ABC123")
+                .header("If-Unmodified-Since-Version", "5")
+                .json_body(serde_json::json!({"title": "New title"}));
+            then.status(204).header("last-modified-version", "6");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let version = zot
+            .update_item("ABC123", &serde_json::json!({"title": "New title"}), 5)
+            .unwrap();
+        assert_eq!(version, 6);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_not_found() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/MISSING");
+            then.status(404)
+                .header("content-type", "text/html")
+                .body("Item not found");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let error = zot.get_item("MISSING", None).unwrap_err();
+        let details = error.api_error().unwrap().clone();
+        assert!(matches!(error, Error::NotFound(_)));
+        assert_eq!(details.status, 404);
+        assert!(details
+            .url
+            .starts_with(&server.url("/users/475425/items/MISSING")));
+        assert_eq!(details.body, "Item not found");
+    }
+
+    #[test]
+    fn test_delete_items_precondition_failed() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/users/475425/items")
+                .query_param("itemKey", "ABC123,DEF456")
+                .header("If-Unmodified-Since-Version", "5");
+            then.status(412)
+                .body("Library has been modified since version 5");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let result = zot.delete_items(&["ABC123", "DEF456"], 5);
+        match result {
+            Err(Error::PreconditionFailed(error)) => {
+                assert_eq!(error.status, 412);
+                assert_eq!(error.body, "Library has been modified since version 5");
+            }
+            other => panic!("expected PreconditionFailed, got {:?}", other),
+        }
+        mock.assert();
+    }
+
+    #[test]
+    fn test_update_collection_parent() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/users/475425/collections/KIMI8BSG")
+                .header("If-Unmodified-Since-Version", "6")
+                .json_body(serde_json::json!({
+                    "key": "KIMI8BSG",
+                    "version": 6,
+                    "name": "LoC",
+                    "parentCollection": "N7W92H48"
+                }));
+            then.status(204).header("last-modified-version", "7");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let data = serde_json::json!({
+            "key": "KIMI8BSG",
+            "version": 6,
+            "name": "LoC",
+            "parentCollection": "N7W92H48"
+        });
+        let version = zot.update_collection("KIMI8BSG", &data, 6).unwrap();
+        assert_eq!(version, 7);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_add_to_collection() {
+        let server = MockServer::start();
+        let item_doc = fs::read_to_string("tests/api_responses/item_doc.json")
+            .expect("Failed to read item_doc.json");
+        let get_mock = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/X42A7DEE");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&item_doc);
+        });
+        let patch_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/X42A7DEE")
+                .header("If-Unmodified-Since-Version", "1")
+                .json_body(serde_json::json!({
+                    "collections": ["BX9965IJ", "9KH9TNSJ", "KIMI8BSG"]
+                }));
+            then.status(204).header("last-modified-version", "2");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let version = zot.add_to_collection("X42A7DEE", "KIMI8BSG").unwrap();
+        assert_eq!(version, 2);
+        get_mock.assert();
+        patch_mock.assert();
+    }
+
+    #[test]
+    fn test_get_items_typed() {
+        let server = MockServer::start();
+        let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
+            .expect("Failed to read items_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&items_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let items = zot.get_items_typed(None).unwrap();
+        assert!(items.iter().all(|item| !item.key.is_empty()));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_query_params() {
+        let server = MockServer::start();
+        let items_doc = fs::read_to_string("tests/api_responses/items_doc.json")
+            .expect("Failed to read items_doc.json");
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("limit", "10")
+                .query_param("sort", "dateModified")
+                .query_param("direction", "desc")
+                .query_param("itemType", "-attachment")
+                .query_param("tag", "a || b")
+                .query_param("tag", "-c");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&items_doc);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new()
+            .limit(10)
+            .sort(Sort::DateModified)
+            .direction(Direction::Desc)
+            .not_item_type("attachment")
+            .tag_any(&["a", "b"])
+            .not_tag("c");
+        zot.get_items(Some(&query)).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_everything_follows_next_links() {
+        let server = MockServer::start();
+        let base = server.base_url();
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/collections")
+                .query_param("start", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("total-results", "3")
+                .header("last-modified-version", "42")
+                .header(
+                    "link",
+                    format!(
+                        "<{0}/users/475425/collections?limit=2&start=2>; rel=\"next\", \
+                         <{0}/users/475425/collections?limit=2&start=2>; rel=\"last\"",
+                        base
+                    ),
+                )
+                .body(r#"[{"key": "A"}, {"key": "B"}]"#);
+        });
+        let second = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/collections")
+                .query_param("start", "2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("total-results", "3")
+                .body(r#"[{"key": "C"}]"#);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().start(0).limit(2);
+        let page = zot.get_page(&Listing::Collections, Some(&query)).unwrap();
+        assert_eq!(page.total_results, Some(3));
+        assert_eq!(page.last_modified_version, Some(42));
+        assert!(page.has_next());
+        assert_eq!(page.links.last, page.links.next);
+
+        let all = zot.everything(&Listing::Collections, Some(&query)).unwrap();
+        let keys: Vec<_> = all.iter().map(|c| c["key"].as_str().unwrap()).collect();
+        assert_eq!(keys, vec!["A", "B", "C"]);
+        first.assert_hits(2);
+        second.assert();
+    }
+
+    #[test]
+    fn test_link_header_with_commas() {
+        let server = MockServer::start();
+        let base = server.base_url();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header(
+                    "link",
+                    format!(
+                        "<{0}/users/475425/items?itemKey=A,B,C&tag=a,b&start=2>; rel=\"next\", \
+                         <{0}/users/475425/items?itemKey=A,B,C&tag=a,b&start=2>; rel=\"last\", \
+                         <https://www.zotero.org/users/475425/items>; rel=\"alternate\"",
+                        base
+                    ),
+                )
+                .body("[]");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().item_keys(&["A", "B", "C"]).limit(2);
+        let page = zot.get_page(&Listing::Items, Some(&query)).unwrap();
+        let next = page.links.next.clone().unwrap();
+        assert_eq!(next.query(), Some("itemKey=A,B,C&tag=a,b&start=2"));
+        assert_eq!(page.links.last, page.links.next);
+        assert_eq!(
+            page.links.alternate.unwrap().as_str(),
+            "https://www.zotero.org/users/475425/items"
+        );
+    }
+
+    fn mock_json<'a>(
+        server: &'a MockServer,
+        path: &str,
+        query: (&str, &str),
+        version: &str,
+        body: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/users/475425/{}", path))
+                .query_param(query.0, query.1);
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("last-modified-version", version)
+                .body(body);
+        })
+    }
+
+    #[test]
+    fn test_sync_store() {
+        let server = MockServer::start();
+        let mocks = vec![
+            mock_json(
+                &server,
+                "collections",
+                ("format", "versions"),
+                "10",
+                r#"{"C1": 5}"#,
+            ),
+            mock_json(&server, "searches", ("format", "versions"), "10", "{}"),
+            mock_json(
+                &server,
+                "items",
+                ("format", "versions"),
+                "10",
+                r#"{"I1": 7}"#,
+            ),
+            mock_json(
+                &server,
+                "collections",
+                ("collectionKey", "C1"),
+                "10",
+                r#"[{"key": "C1", "version": 5, "data": {"name": "New"}}]"#,
+            ),
+            mock_json(
+                &server,
+                "items",
+                ("itemKey", "I1"),
+                "10",
+                r#"[{"key": "I1", "version": 7, "data": {"itemType": "book"}}]"#,
+            ),
+            mock_json(
+                &server,
+                "deleted",
+                ("since", "3"),
+                "10",
+                r#"{"items": ["I0"]}"#,
+            ),
+        ];
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let mut store = MemoryStore::default();
+        store
+            .upsert(ObjectKind::Item, &[serde_json::json!({"key": "I0"})])
+            .unwrap();
+        store.set_library_version(3).unwrap();
+
+        let version = zot.sync_store(&mut store).unwrap();
+        assert_eq!(version, 10);
+        assert_eq!(store.library_version().unwrap(), 10);
+        assert!(store.get(ObjectKind::Item, "I0").is_none());
+        assert_eq!(store.get(ObjectKind::Item, "I1").unwrap()["version"], 7);
+        assert_eq!(
+            store.get(ObjectKind::Collection, "C1").unwrap()["data"]["name"],
+            "New"
+        );
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn test_sync_conflict() {
+        let server = MockServer::start();
+        let collections = mock_json(&server, "collections", ("format", "versions"), "10", "{}");
+        mock_json(&server, "searches", ("format", "versions"), "11", "{}");
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let result = zot.get_changes_since(0);
+        assert!(matches!(result, Err(Error::SyncConflict(_))));
+        collections.assert_hits(3);
+    }
+
+    #[test]
+    fn test_upload_attachment() {
+        let server = MockServer::start();
+        let path = std::env::temp_dir().join("zotero_rs_sync_upload.pdf");
+        fs::write(&path, b"%PDF-1.4 test").unwrap();
+        let md5 = format!("{:x}", md5::compute(b"%PDF-1.4 test"));
+
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .json_body_partial(r#"[{"itemType": "attachment", "parentItem": "PARENT"}]"#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"success": {"0": "ATTACH1"}, "unchanged": {}, "failed": {}}"#);
+        });
+        let authorize = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-None-Match", "*")
+                .x_www_form_urlencoded_tuple("md5", &md5)
+                .x_www_form_urlencoded_tuple("filename", "zotero_rs_sync_upload.pdf")
+                .x_www_form_urlencoded_tuple("filesize", "13");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "url": server.url("/s3upload"),
+                    "contentType": "multipart/form-data; boundary=XYZ",
+                    "prefix": "PREFIX",
+                    "suffix": "SUFFIX",
+                    "uploadKey": "UPLOADKEY"
+                }));
+        });
+        let storage = server.mock(|when, then| {
+            when.method(POST)
+                .path("/s3upload")
+                .header("content-type", "multipart/form-data; boundary=XYZ")
+                .body("PREFIX%PDF-1.4 testSUFFIX");
+            then.status(201);
+        });
+        let register = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-None-Match", "*")
+                .x_www_form_urlencoded_tuple("upload", "UPLOADKEY");
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let upload = zot.upload_attachment(&path, Some("PARENT")).unwrap();
+        assert_eq!(upload.key, "ATTACH1");
+        assert_eq!(upload.outcome, UploadOutcome::Uploaded);
+        create.assert();
+        authorize.assert();
+        storage.assert();
+        register.assert();
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_upload_file_exists() {
+        let server = MockServer::start();
+        let authorize = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-Match", "oldmd5");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"exists": 1}"#);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", b"contents", 1_700_000_000_000);
+        let outcome = zot
+            .upload_file("ATTACH1", &info, b"contents", Some("oldmd5"))
+            .unwrap();
+        assert_eq!(outcome, UploadOutcome::Exists);
+        authorize.assert();
+    }
+
+    fn mock_patch_authorization<'a>(
+        server: &'a MockServer,
+        previous_md5: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-Match", previous_md5)
+                .x_www_form_urlencoded_tuple("filename", "paper.pdf");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "url": server.url("/s3upload"),
+                    "contentType": "application/pdf",
+                    "prefix": "",
+                    "suffix": "",
+                    "uploadKey": "UPLOADKEY"
+                }));
+        })
+    }
+
+    #[test]
+    fn test_update_attachment_file_patch() {
+        let server = MockServer::start();
+        let previous = b"%PDF-1.4 first version of the file";
+        let contents = b"%PDF-1.4 second version of the file";
+        let previous_md5 = format!("{:x}", md5::compute(previous));
+        let authorize = mock_patch_authorization(&server, &previous_md5);
+        let patch = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ATTACH1/file")
+                .query_param("algorithm", "bsdiff")
+                .query_param("upload", "UPLOADKEY")
+                .header("If-Match", &previous_md5);
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", contents, 1_700_000_000_000);
+        let outcome = zot
+            .update_attachment_file("ATTACH1", previous, &info, contents)
+            .unwrap();
+        assert_eq!(outcome, UploadOutcome::Patched);
+        authorize.assert();
+        patch.assert();
+    }
+
+    #[test]
+    fn test_update_attachment_file_falls_back_to_full_upload() {
+        let server = MockServer::start();
+        let previous = b"%PDF-1.4 first version of the file";
+        let contents = b"%PDF-1.4 second version of the file";
+        let previous_md5 = format!("{:x}", md5::compute(previous));
+        let authorize = mock_patch_authorization(&server, &previous_md5);
+        let patch = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ATTACH1/file");
+            then.status(400).body("Unsupported algorithm");
+        });
+        let storage = server.mock(|when, then| {
+            when.method(POST)
+                .path("/s3upload")
+                .body("%PDF-1.4 second version of the file");
+            then.status(201);
+        });
+        let register = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("If-Match", &previous_md5)
+                .x_www_form_urlencoded_tuple("upload", "UPLOADKEY");
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", contents, 1_700_000_000_000);
+        let outcome = zot
+            .update_attachment_file("ATTACH1", previous, &info, contents)
+            .unwrap();
+        assert_eq!(outcome, UploadOutcome::Uploaded);
+        authorize.assert();
+        patch.assert();
+        storage.assert();
+        register.assert();
+    }
+
+    #[test]
+    fn test_update_attachment_file_reports_forbidden_patch() {
+        let server = MockServer::start();
+        let previous = b"%PDF-1.4 first version of the file";
+        let contents = b"%PDF-1.4 second version of the file";
+        let previous_md5 = format!("{:x}", md5::compute(previous));
+        let authorize = mock_patch_authorization(&server, &previous_md5);
+        let patch = server.mock(|when, then| {
+            when.method(httpmock::Method::PATCH)
+                .path("/users/475425/items/ATTACH1/file");
+            then.status(403).body("File editing denied");
+        });
+        let storage = server.mock(|when, then| {
+            when.method(POST).path("/s3upload");
+            then.status(201);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let info = FileInfo::new("paper.pdf", contents, 1_700_000_000_000);
+        let result = zot.update_attachment_file("ATTACH1", previous, &info, contents);
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+        authorize.assert();
+        patch.assert();
+        storage.assert_hits(0);
+    }
+
+    fn mock_attachment_item<'a>(server: &'a MockServer, contents: &[u8]) -> httpmock::Mock<'a> {
+        let item = serde_json::json!({
+            "key": "ATTACH1",
+            "version": 1,
+            "data": {
+                "key": "ATTACH1",
+                "itemType": "attachment",
+                "md5": format!("{:x}", md5::compute(contents)),
+                "mtime": 1_326_311_686_000i64
+            }
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(item);
+        })
+    }
+
+    #[test]
+    fn test_download_file_to_resumes() {
+        let server = MockServer::start();
+        let contents = b"0123456789abcdefghij";
+        let item = mock_attachment_item(&server, contents);
+        let file = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items/ATTACH1/file")
+                .header("range", "bytes=10-");
+            then.status(206)
+                .header("content-type", "application/pdf")
+                .body(&contents[10..]);
+        });
+
+        let dir = std::env::temp_dir().join("zotero_rs_sync_download");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("paper.pdf");
+        fs::remove_file(&path).ok();
+        fs::write(dir.join("paper.pdf.part"), &contents[..10]).unwrap();
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let size = zot.download_file_to("ATTACH1", &path).unwrap();
+        assert_eq!(size, 20);
+        assert_eq!(fs::read(&path).unwrap(), contents);
+        assert!(!dir.join("paper.pdf.part").exists());
+        let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(
+            mtime,
+            std::time::UNIX_EPOCH + std::time::Duration::from_millis(1_326_311_686_000)
+        );
+        item.assert();
+        file.assert();
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_download_file_to_checksum_mismatch() {
+        let server = MockServer::start();
+        mock_attachment_item(&server, b"expected contents");
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1/file");
+            then.status(200)
+                .header("content-type", "application/pdf")
+                .body("corrupted contents");
+        });
+
+        let dir = std::env::temp_dir().join("zotero_rs_sync_download_mismatch");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("paper.pdf");
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let result = zot.download_file_to("ATTACH1", &path);
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+        assert!(!path.exists());
+        assert!(!dir.join("paper.pdf.part").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_get_attachment_file_snapshot() {
+        let server = MockServer::start();
+        let mut files = BTreeMap::new();
+        files.insert("page.html".to_string(), b"<html></html>".to_vec());
+        files.insert("style.css".to_string(), b"body {}".to_vec());
+        let zip = Snapshot::new(files.clone(), "page.html").to_zip().unwrap();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/SNAP1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "key": "SNAP1",
+                    "data": {
+                        "linkMode": "imported_url",
+                        "contentType": "text/html",
+                        "filename": "page.html"
+                    }
+                }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/SNAP1/file");
+            then.status(200)
+                .header("content-type", "application/zip")
+                .body(&zip);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        match zot.get_attachment_file("SNAP1").unwrap() {
+            AttachmentFile::Snapshot(snapshot) => {
+                assert_eq!(snapshot.files, files);
+                assert_eq!(snapshot.main_html(), Some(&b"<html></html>"[..]));
+            }
+            AttachmentFile::File(_) => panic!("expected a snapshot"),
+        }
+    }
+
+    #[test]
+    fn test_get_attachment_file_zip_kept_as_is() {
+        let server = MockServer::start();
+        let mut files = BTreeMap::new();
+        files.insert("data.csv".to_string(), b"a,b".to_vec());
+        let zip = Snapshot::new(files, "data.csv").to_zip().unwrap();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ZIP1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "key": "ZIP1",
+                    "data": {
+                        "linkMode": "imported_file",
+                        "contentType": "application/zip",
+                        "filename": "data.zip"
+                    }
+                }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ZIP1/file");
+            then.status(200)
+                .header("content-type", "application/zip")
+                .body(&zip);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        match zot.get_attachment_file("ZIP1").unwrap() {
+            AttachmentFile::File(contents) => assert_eq!(contents, zip),
+            AttachmentFile::Snapshot(_) => panic!("expected the file as stored"),
+        }
+    }
+
+    #[test]
+    fn test_upload_snapshot() {
+        let server = MockServer::start();
+        let mut files = BTreeMap::new();
+        files.insert("page.html".to_string(), b"<html></html>".to_vec());
+        let snapshot = Snapshot::new(files, "page.html");
+        let zip = snapshot.to_zip().unwrap();
+        let authorize = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/SNAP1/file")
+                .header("If-None-Match", "*")
+                .x_www_form_urlencoded_tuple("filename", "page.html")
+                .x_www_form_urlencoded_tuple("md5", format!("{:x}", md5::compute(b"<html></html>")))
+                .x_www_form_urlencoded_tuple("zipMD5", format!("{:x}", md5::compute(&zip)))
+                .x_www_form_urlencoded_tuple("zipFilename", "SNAP1.zip");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "url": server.url("/s3upload"),
+                    "contentType": "application/zip",
+                    "prefix": "",
+                    "suffix": "",
+                    "uploadKey": "UPLOADKEY"
+                }));
+        });
+        let storage = server.mock(|when, then| {
+            when.method(POST)
+                .path("/s3upload")
+                .header("content-type", "application/zip");
+            then.status(201);
+        });
+        let register = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items/SNAP1/file")
+                .x_www_form_urlencoded_tuple("upload", "UPLOADKEY");
+            then.status(204);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let outcome = zot.upload_snapshot("SNAP1", &snapshot, None).unwrap();
+        assert_eq!(outcome, UploadOutcome::Uploaded);
+        authorize.assert();
+        storage.assert();
+        register.assert();
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let server = MockServer::start();
+        let file = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ATTACH1/file");
+            then.status(503).body("Service Unavailable");
+        });
+        let version = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(500);
+        });
+        let not_found = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/MISSING");
+            then.status(404);
+        });
+
+        let policy = RetryPolicy::new()
+            .max_attempts(3)
+            .base_delay(Duration::from_millis(1))
+            .jitter(0.0);
+        let mut zot = Zotero::user_lib("475425", "myuserkey")
+            .unwrap()
+            .with_retry_policy(policy);
+        zot.set_endpoint(&server.base_url());
+
+        let result = zot.get_file("ATTACH1", None);
+        assert!(matches!(result, Err(Error::ServiceUnavailable(_))));
+        file.assert_hits(3);
+
+        let result = zot.get_last_modified_version(None);
+        assert!(matches!(result, Err(Error::ServerError(_))));
+        version.assert_hits(3);
+
+        let result = zot.get_item("MISSING", None);
+        assert!(matches!(result, Err(Error::NotFound(_))));
+        not_found.assert_hits(1);
+    }
+
+    /// Write tokens of create requests seen by `test_retried_create_reuses_write_token`.
+    static WRITE_TOKENS: std::sync::Mutex<std::collections::BTreeSet<String>> =
+        std::sync::Mutex::new(std::collections::BTreeSet::new());
+
+    fn write_token(req: &HttpMockRequest) -> Option<String> {
+        req.headers
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("zotero-write-token"))
+            .map(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn test_retried_create_reuses_write_token() {
+        let server = MockServer::start();
+        // Only a token seen on an earlier attempt succeeds, so the create
+        // goes through only if the retry repeats the token.
+        let retried = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .matches(|req| {
+                    let tokens = WRITE_TOKENS.lock().unwrap();
+                    write_token(req).is_some_and(|token| tokens.contains(&token))
+                });
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("last-modified-version", "2")
+                .json_body(serde_json::json!({"success": {"0": "ABCD2345"}}));
+        });
+        let first = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/items")
+                .matches(|req| {
+                    let Some(token) = write_token(req) else {
+                        return false;
+                    };
+                    WRITE_TOKENS.lock().unwrap().insert(token);
+                    true
+                });
+            then.status(500);
+        });
+        let upload = server.mock(|when, then| {
+            when.method(POST).path("/users/475425/items/ATTACH1/file");
+            then.status(500);
+        });
+
+        let policy = RetryPolicy::new()
+            .max_attempts(3)
+            .base_delay(Duration::from_millis(1))
+            .jitter(0.0);
+        let mut zot = Zotero::user_lib("475425", "myuserkey")
+            .unwrap()
+            .with_retry_policy(policy);
+        zot.set_endpoint(&server.base_url());
+
+        let response = zot
+            .create_items(&[serde_json::json!({"itemType": "book"})], None)
+            .unwrap();
+        assert_eq!(response.success[&0], "ABCD2345");
+        first.assert_hits(1);
+        retried.assert_hits(1);
+
+        // Upload requests carry no write token and are not replayed.
+        let info = FileInfo::new("paper.pdf", b"contents", 1_700_000_000_000);
+        let result = zot.upload_file("ATTACH1", &info, b"contents", None);
+        assert!(matches!(result, Err(Error::ServerError(_))));
+        upload.assert_hits(1);
+    }
+
+    #[test]
+    fn test_backoff_header_delays_later_requests() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("backoff", "0.3")
+                .body("[]");
+        });
+        let item = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ABC123");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let clone = zot.clone();
+        let start = std::time::Instant::now();
+        zot.get_items(None).unwrap();
+        let worker = std::thread::spawn(move || clone.get_item("ABC123", None).unwrap());
+        worker.join().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        item.assert();
+    }
+
+    #[test]
+    fn test_rate_limiter_shared_by_clones() {
+        let server = MockServer::start();
+        let item = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items/ABC123");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey")
+            .unwrap()
+            .with_rate_limiter(RateLimiter::new(10.0, 1));
+        zot.set_endpoint(&server.base_url());
+        let start = std::time::Instant::now();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let zot = zot.clone();
+                std::thread::spawn(move || zot.get_item("ABC123", None).unwrap())
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        item.assert_hits(4);
+    }
+
+    #[test]
+    fn test_builder() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/groups/123456/items/ABC123")
+                .query_param("locale", "de-DE")
+                .header("user-agent", "zotero-rust/1 my-tool/0.1")
+                .header("authorization", "Bearer myuserkey");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        });
+
+        let zot = ZoteroBuilder::new("myuserkey")
+            .group_library(123456)
+            .endpoint(&format!("{}/", server.base_url()))
+            .locale("de-DE")
+            .user_agent_suffix("my-tool/0.1")
+            .timeout(Duration::from_secs(5))
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        zot.get_item("ABC123", None).unwrap();
+        mock.assert();
+
+        let missing_library = ZoteroBuilder::new("myuserkey").build();
+        assert!(matches!(missing_library, Err(Error::ConfigError(_))));
+        let bad_endpoint = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint("api.zotero.org")
+            .build();
+        assert!(matches!(bad_endpoint, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_client_without_key() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/groups/123456/items/ABC123")
+                .matches(|req| {
+                    let headers = req.headers.as_deref().unwrap_or_default();
+                    !headers
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+                });
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({"key": "ABC123", "version": 1, "data": {}}));
+        });
+
+        let mut zot = Zotero::group_lib("123456", " ").unwrap();
+        zot.set_endpoint(&server.base_url());
+        zot.get_item("ABC123", None).unwrap();
+        mock.assert();
+        assert!(matches!(zot.get_key_info(None), Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_switch_library() {
+        let server = MockServer::start();
+        let user = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/collections");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("[]");
+        });
+        let group = server.mock(|when, then| {
+            when.method(GET).path("/groups/1/collections");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("[]");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let group_zot = zot.for_library(Library::Group(1));
+        assert_eq!(zot.library(), Library::User(475425));
+        assert_eq!(group_zot.library(), Library::Group(1));
+        zot.get_collections(None).unwrap();
+        group_zot.get_collections(None).unwrap();
+        zot.set_library("groups/1".parse().unwrap());
+        zot.get_collections(None).unwrap();
+        user.assert_hits(1);
+        group.assert_hits(2);
+
+        let invalid = Zotero::user_lib("myuserID", "myuserkey");
+        assert!(matches!(invalid, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_get_accessible_libraries() {
+        let server = MockServer::start();
+        let groups_doc = fs::read_to_string("tests/api_responses/groups_doc.json").unwrap();
+        let key = server.mock(|when, then| {
+            when.method(GET).path("/keys/myuserkey");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "key": "myuserkey",
+                    "userID": 475425,
+                    "username": "someone",
+                    "access": {
+                        "user": {"library": true, "files": true, "notes": true, "write": false},
+                        "groups": {
+                            "all": {"library": true, "write": false},
+                            "169947": {"library": true, "write": true}
+                        }
+                    }
+                }));
+        });
+        let groups = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/groups");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&groups_doc);
+        });
+
+        let mut zot = Zotero::group_lib("1", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let libraries = zot.get_accessible_libraries().unwrap();
+        key.assert();
+        groups.assert();
+        // A group client lists the groups of the key's user.
+        let listed = zot.get_groups_typed(None).unwrap();
+        assert_eq!(listed[0].id, 169947);
+        key.assert_hits(2);
+        groups.assert_hits(2);
+
+        assert_eq!(libraries.len(), 2);
+        assert_eq!(libraries[0].library, Library::User(475425));
+        assert_eq!(libraries[0].name, "someone");
+        assert!(libraries[0].permissions.files && !libraries[0].permissions.write);
+        assert_eq!(libraries[1].library, Library::Group(169947));
+        assert_eq!(libraries[1].client.library(), Library::Group(169947));
+        assert_eq!(libraries[1].name, "smart_cities");
+        assert!(libraries[1].permissions.write);
+    }
+
+    #[test]
+    fn test_key_info_without_user() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/keys/myuserkey");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({"key": "myuserkey", "access": {}}));
+        });
+
+        let mut zot = Zotero::group_lib("1", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        assert!(matches!(
+            zot.get_accessible_libraries(),
+            Err(Error::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            zot.get_groups(None),
+            Err(Error::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_saved_searches() {
+        let server = MockServer::start();
+        let search = serde_json::json!({
+            "key": "HHF7BB4C",
+            "version": 7,
+            "data": {
+                "key": "HHF7BB4C",
+                "version": 7,
+                "name": "Climate books",
+                "conditions": [
+                    {"condition": "itemType", "operator": "is", "value": "book"},
+                    {"condition": "title", "operator": "contains", "value": "climate"}
+                ]
+            }
+        });
+        let create = server.mock(|when, then| {
+            when.method(POST)
+                .path("/users/475425/searches")
+                .json_body(serde_json::json!([search["data"]]));
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!({
+                    "successful": {"0": search.clone()},
+                    "success": {"0": "HHF7BB4C"},
+                    "unchanged": {},
+                    "failed": {}
+                }));
+        });
+        let get = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/searches/HHF7BB4C");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(search.clone());
+        });
+        let items = server.mock(|when, then| {
+            when.method(GET).path("/users/475425/items");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(serde_json::json!([
+                    {"key": "AAAA0001", "version": 1, "data": {"itemType": "book", "title": "Climate Models"}},
+                    {"key": "AAAA0002", "version": 1, "data": {"itemType": "book", "title": "Ocean currents"}},
+                    {"key": "AAAA0003", "version": 1, "data": {"itemType": "report", "title": "Climate report"}}
+                ]));
+        });
+        let delete = server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/users/475425/searches")
+                .query_param("searchKey", "HHF7BB4C")
+                .header("If-Unmodified-Since-Version", "7");
+            then.status(204).header("last-modified-version", "8");
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let resp = zot
+            .create_searches(&[search["data"].clone()], None)
+            .unwrap();
+        assert_eq!(resp.success.get(&0).map(String::as_str), Some("HHF7BB4C"));
+
+        let matches = zot.execute_search("HHF7BB4C").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].key, "AAAA0001");
+
+        assert_eq!(zot.delete_searches(&["HHF7BB4C"], 7).unwrap(), 8);
+        create.assert();
+        get.assert();
+        items.assert();
+        delete.assert();
+    }
+
+    fn mock_export<'a>(
+        server: &'a MockServer,
+        format: &str,
+        content_type: &str,
+        pages: [&str; 2],
+    ) -> (httpmock::Mock<'a>, httpmock::Mock<'a>) {
+        let next = format!(
+            "<{}/users/475425/items?format={}&limit=100&start=100>; rel=\"next\"",
+            server.base_url(),
+            format
+        );
+        let second = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", format)
+                .query_param("start", "100");
+            then.status(200)
+                .header("content-type", content_type)
+                .body(pages[1]);
+        });
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", format)
+                .query_param("limit", "100");
+            then.status(200)
+                .header("content-type", content_type)
+                .header("link", next)
+                .body(pages[0]);
+        });
+        (first, second)
+    }
+
+    #[test]
+    fn test_get_items_formatted() {
+        let server = MockServer::start();
+        let (first, second) = mock_export(
+            &server,
+            "bibtex",
+            "application/x-bibtex",
+            ["@book{a,\n  title = {A}\n}", "@book{b,\n  title = {B}\n}\n"],
+        );
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let bibtex = zot.get_items_formatted(Format::Bibtex, None).unwrap();
+        assert_eq!(
+            bibtex,
+            "@book{a,\n  title = {A}\n}\n@book{b,\n  title = {B}\n}\n"
+        );
+        first.assert();
+        second.assert();
+
+        mock_export(
+            &server,
+            "csv",
+            "text/csv",
+            [
+                "\u{feff}\"Key\",\"Title\"\n\"A\",\"One\"\n",
+                "\u{feff}\"Key\",\"Title\"\n\"B\",\"Two\"\n",
+            ],
+        );
+        let csv = zot.get_items_formatted(Format::Csv, None).unwrap();
+        assert_eq!(csv, "\"Key\",\"Title\"\n\"A\",\"One\"\n\"B\",\"Two\"\n");
+
+        mock_export(
+            &server,
+            "mods",
+            "application/mods+xml",
+            [
+                "<?xml version=\"1.0\"?>\n<modsCollection><mods ID=\"a\"/></modsCollection>",
+                "<?xml version=\"1.0\"?>\n<modsCollection><mods ID=\"b\"/></modsCollection>",
+            ],
+        );
+        let mods = zot.get_items_formatted(Format::Mods, None).unwrap();
+        assert_eq!(
+            mods,
+            "<?xml version=\"1.0\"?>\n<modsCollection><mods ID=\"a\"/><mods ID=\"b\"/></modsCollection>"
+        );
+
+        assert!(matches!(
+            zot.get_items_formatted(Format::Keys, None),
+            Err(Error::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_bibliography() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "bib")
+                .query_param("style", "apa")
+                .query_param("locale", "de-DE")
+                .matches(|req| {
+                    let params = req.query_params.as_deref().unwrap_or_default();
+                    params.iter().filter(|(key, _)| key == "locale").count() == 1
+                })
+                .query_param("linkwrap", "1")
+                .query_param("itemKey", "ABC123");
+            then.status(200)
+                .header("content-type", "text/html; charset=UTF-8")
+                .body(
+                    "<div class=\"csl-bib-body\"><div class=\"csl-entry\">Smith, A. (2014). \
+                     <i>Cities</i>.</div></div>",
+                );
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().item_keys(&["ABC123"]).linkwrap(true);
+        let bibliography = zot
+            .get_bibliography("apa", Some("de-DE"), Some(&query))
+            .unwrap();
+        assert_eq!(bibliography.text(), "Smith, A. (2014). Cities.");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_get_feed() {
+        let server = MockServer::start();
+        let atom = fs::read_to_string("tests/api_responses/items_atom.xml")
+            .expect("Failed to read items_atom.xml")
+            .replace("https://api.zotero.org", &server.base_url());
+        let next = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "atom")
+                .query_param("start", "2");
+            then.status(200)
+                .header("content-type", "application/atom+xml")
+                .body(r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Items</title></feed>"#);
+        });
+        let first = server.mock(|when, then| {
+            when.method(GET)
+                .path("/users/475425/items")
+                .query_param("format", "atom")
+                .query_param("content", "json");
+            then.status(200)
+                .header("content-type", "application/atom+xml")
+                .body(&atom);
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        let query = Query::new().content(&["json"]).limit(2);
+        let feed = zot.get_feed(&Listing::Items, Some(&query)).unwrap();
+        assert_eq!(feed.entries[1].key.as_deref(), Some("X42A7DEE"));
+        let last = zot.next_feed(&feed).unwrap().unwrap();
+        assert!(last.entries.is_empty());
+        assert!(zot.next_feed(&last).unwrap().is_none());
+        first.assert();
+        next.assert();
+    }
+
+    #[test]
+    fn test_item_template_validation() {
+        let server = MockServer::start();
+        let template_doc = fs::read_to_string("tests/api_responses/item_template.json")
+            .expect("Failed to read item_template.json");
+        let template = server.mock(|when, then| {
+            when.method(GET)
+                .path("/items/new")
+                .query_param("itemType", "book");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&template_doc);
+        });
+        let schema = server.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(fs::read_to_string("tests/api_responses/schema.json").unwrap());
+        });
+
+        let mut zot = Zotero::user_lib("475425", "myuserkey").unwrap();
+        zot.set_endpoint(&server.base_url());
+        zot.set_schema_cache(None);
+        let mut item = zot.get_item_template("book", None).unwrap();
+        item["title"] = serde_json::json!("Cities");
+        zot.validate_item(&item).unwrap();
+
+        item["creators"][0]["creatorType"] = serde_json::json!("composer");
+        item["DOI"] = serde_json::json!("10.4324/9780203641163");
+        match zot.validate_item(&item) {
+            Err(Error::InvalidItem(violations)) => assert_eq!(violations.len(), 2),
+            other => panic!("expected InvalidItem, got {:?}", other),
+        }
+        template.assert();
+        schema.assert();
+    }
+
+    #[test]
+    fn test_get_schema_cached() {
+        let server = MockServer::start();
+        let path = std::env::temp_dir().join("zotero_rs_sync_schema.json");
+        let _ = fs::remove_file(&path);
+        let unchanged = server.mock(|when, then| {
+            when.method(GET)
+                .path("/schema")
+                .header("If-None-Match", "\"schema-29\"");
+            then.status(304);
+        });
+        let schema_doc = fs::read_to_string("tests/api_responses/schema.json").unwrap();
+        let fetched = server.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(200)
+                .header("content-type", "application/json")
+                .header("ETag", "\"schema-29\"")
+                .body(&schema_doc);
+        });
+
+        let zot = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint(&server.base_url())
+            .schema_cache(&path)
+            .build()
+            .unwrap();
+        let schema = zot.get_schema().unwrap();
+        assert_eq!(schema.version, 29);
+        assert!(path.exists());
+        let fields = zot.get_item_type_fields("book").unwrap();
+        assert_eq!(fields[0]["field"], "title");
+        assert_eq!(fields[0]["localized"], "Title");
+        let book = zot.get_item_type_schema("book").unwrap();
+        assert!(book.creator_types.contains("author"));
+        assert_eq!(zot.get_item_fields().unwrap().as_array().unwrap().len(), 10);
+        match zot.get_item_type_fields("dataset") {
+            Err(Error::UnknownItemType(item_type)) => assert_eq!(item_type, "dataset"),
+            other => panic!("expected UnknownItemType, got {:?}", other),
+        }
+        fetched.assert();
+
+        // A new session revalidates the cached schema.
+        let zot = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint(&server.base_url())
+            .schema_cache(&path)
+            .build()
+            .unwrap();
+        assert_eq!(zot.get_schema().unwrap(), schema);
+        unchanged.assert();
+        fetched.assert();
+
+        // Without a connection, the cached schema is used.
+        let offline = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint("http://127.0.0.1:1")
+            .retry_policy(RetryPolicy::none())
+            .locale("de-DE")
+            .schema_cache(&path)
+            .build()
+            .unwrap();
+        let fields = offline.get_item_type_fields("book").unwrap();
+        assert_eq!(fields[0]["localized"], "Titel");
+        assert_eq!(fields[4]["localized"], "ISBN");
+        assert!(offline
+            .get_item_type_schema("book")
+            .unwrap()
+            .fields
+            .contains("ISBN"));
+
+        // Server errors fall back to the cache, client errors do not.
+        let failing = MockServer::start();
+        failing.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(503);
+        });
+        let forbidden = MockServer::start();
+        forbidden.mock(|when, then| {
+            when.method(GET).path("/schema");
+            then.status(403);
+        });
+        let client = |endpoint: &str| {
+            Zotero::builder("myuserkey")
+                .user_library(475425)
+                .endpoint(endpoint)
+                .retry_policy(RetryPolicy::none())
+                .schema_cache(&path)
+                .build()
+                .unwrap()
+        };
+        assert_eq!(client(&failing.base_url()).get_schema().unwrap(), schema);
+        assert!(matches!(
+            client(&forbidden.base_url()).get_schema(),
+            Err(Error::Forbidden(_))
+        ));
+        fs::remove_file(&path).unwrap();
+
+        // A schema that cannot be written to disk is still kept in memory.
+        let blocked = std::env::temp_dir().join("zotero_rs_sync_schema_blocked");
+        fs::write(&blocked, "not a directory").unwrap();
+        let zot = Zotero::builder("myuserkey")
+            .user_library(475425)
+            .endpoint(&server.base_url())
+            .schema_cache(&blocked.join("schema.json"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            zot.get_item_type_fields("book").unwrap()[0]["field"],
+            "title"
+        );
+        fetched.assert_hits(2);
+        fs::remove_file(&blocked).unwrap();
+    }
 }
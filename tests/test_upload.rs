@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod upload_tests {
+    use bzip2::read::BzDecoder;
+    use std::io::Read;
+    use zotero_rs::upload::bsdiff_patch;
+    use zotero_rs::FileInfo;
+
+    fn bunzip(block: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        BzDecoder::new(block).read_to_end(&mut data).unwrap();
+        data
+    }
+
+    fn offtin(buf: &[u8]) -> i64 {
+        let value = u64::from_le_bytes(buf.try_into().unwrap());
+        let magnitude = (value & !(1 << 63)) as i64;
+        match value >> 63 {
+            0 => magnitude,
+            _ => -magnitude,
+        }
+    }
+
+    /// Applies a `BSDIFF40` patch the way the reference `bspatch` does.
+    fn bspatch(old: &[u8], patch: &[u8]) -> Vec<u8> {
+        assert_eq!(&patch[..8], b"BSDIFF40");
+        let ctrl_len = offtin(&patch[8..16]) as usize;
+        let diff_len = offtin(&patch[16..24]) as usize;
+        let new_len = offtin(&patch[24..32]) as usize;
+        let ctrl = bunzip(&patch[32..32 + ctrl_len]);
+        let diff = bunzip(&patch[32 + ctrl_len..32 + ctrl_len + diff_len]);
+        let extra = bunzip(&patch[32 + ctrl_len + diff_len..]);
+
+        let mut new = Vec::with_capacity(new_len);
+        let (mut diff_pos, mut extra_pos, mut old_pos) = (0, 0, 0i64);
+        for triple in ctrl.chunks(24) {
+            let add = offtin(&triple[..8]) as usize;
+            let copy = offtin(&triple[8..16]) as usize;
+            for &byte in &diff[diff_pos..diff_pos + add] {
+                let old_byte = usize::try_from(old_pos)
+                    .ok()
+                    .and_then(|pos| old.get(pos))
+                    .copied()
+                    .unwrap_or(0);
+                new.push(byte.wrapping_add(old_byte));
+                old_pos += 1;
+            }
+            diff_pos += add;
+            new.extend_from_slice(&extra[extra_pos..extra_pos + copy]);
+            extra_pos += copy;
+            old_pos += offtin(&triple[16..24]);
+        }
+        assert_eq!(new.len(), new_len);
+        assert_eq!((diff_pos, extra_pos), (diff.len(), extra.len()));
+        new
+    }
+
+    #[test]
+    fn test_file_info() {
+        let info = FileInfo::new("Paper.PDF", b"One very strange PDF\n", 1_326_311_686_000);
+        assert_eq!(info.content_type, "application/pdf");
+        assert_eq!(info.filesize, 21);
+        assert_eq!(
+            info.md5,
+            format!("{:x}", md5::compute(b"One very strange PDF\n"))
+        );
+
+        let item = info.attachment_item(Some("PARENT"));
+        assert_eq!(item["linkMode"], "imported_file");
+        assert_eq!(item["parentItem"], "PARENT");
+    }
+
+    #[test]
+    fn test_bsdiff_patch_header() {
+        let old = b"The quick brown fox jumps over the lazy dog".repeat(20);
+        let mut new = old.clone();
+        new.extend_from_slice(b" and runs away");
+        let patch = bsdiff_patch(&old, &new).unwrap();
+
+        assert_eq!(&patch[..8], b"BSDIFF40");
+        let field = |i: usize| u64::from_le_bytes(patch[8 * i..8 * i + 8].try_into().unwrap());
+        assert_eq!(field(3), new.len() as u64);
+        assert!(32 + field(1) + field(2) <= patch.len() as u64);
+        assert_eq!(&patch[32..35], b"BZh");
+    }
+
+    #[test]
+    fn test_bsdiff_patch_round_trip() {
+        let old: Vec<u8> = (0..4000u32)
+            .flat_map(|n| format!("line {} of the old file\n", n * 7 % 1000).into_bytes())
+            .collect();
+
+        let mut edited = old.clone();
+        edited[1000..1010].copy_from_slice(b"0123456789");
+        edited.splice(5000..5000, b"inserted text".iter().copied());
+        let mut swapped = old[old.len() / 2..].to_vec();
+        swapped.extend_from_slice(&old[..old.len() / 2]);
+        // Shorter than the old file, with blocks taken out of order so that
+        // the patch seeks backwards in the old file.
+        let mut shorter = old[60_000..70_000].to_vec();
+        shorter.extend_from_slice(b"new tail");
+        shorter.extend_from_slice(&old[10_000..15_000]);
+
+        for new in [edited, swapped, shorter, Vec::new()] {
+            let patch = bsdiff_patch(&old, &new).unwrap();
+            assert_eq!(bspatch(&old, &patch), new);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod validate_tests {
+    use serde_json::{json, Value};
+    use std::fs;
+    use zotero_rs::{Error, ItemTypeSchema, Violation};
+
+    fn book_schema() -> ItemTypeSchema {
+        ItemTypeSchema::from_responses(
+            "book",
+            &json!([
+                {"field": "title", "localized": "Title"},
+                {"field": "url", "localized": "URL"},
+                {"field": "publisher", "localized": "Publisher"}
+            ]),
+            &json!([
+                {"creatorType": "author", "localized": "Author"},
+                {"creatorType": "editor", "localized": "Editor"}
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_template_is_valid() {
+        let doc = fs::read_to_string("tests/api_responses/item_template.json")
+            .expect("Failed to read item_template.json");
+        let mut template: Value = serde_json::from_str(&doc).unwrap();
+        template["title"] = json!("Cities and the Creative Class");
+        template["creators"][0]["lastName"] = json!("Florida");
+        assert!(book_schema().validate(&template).is_empty());
+        assert!(book_schema().check(&template).is_ok());
+    }
+
+    #[test]
+    fn test_reports_all_violations() {
+        let item = json!({
+            "itemType": "book",
+            "title": "Cities",
+            "DOI": "10.1/x",
+            "publisher": 42,
+            "creators": [
+                {"creatorType": "director", "name": "A. Director"},
+                {"creatorType": "author", "name": "Jane", "lastName": "Doe"}
+            ],
+            "tags": ["not-an-object"]
+        });
+        let violations = book_schema().validate(&item);
+        assert_eq!(
+            violations,
+            vec![
+                Violation::UnknownField("DOI".to_string()),
+                Violation::InvalidCreatorType {
+                    index: 0,
+                    creator_type: "director".to_string()
+                },
+                Violation::InvalidCreatorName { index: 1 },
+                Violation::InvalidValue {
+                    field: "publisher".to_string(),
+                    expected: "a string"
+                },
+                Violation::InvalidValue {
+                    field: "tags".to_string(),
+                    expected: "a list of {\"tag\": ...} objects"
+                },
+            ]
+        );
+
+        match book_schema().check(&json!({"itemType": "journalArticle"})) {
+            Err(Error::InvalidItem(violations)) => assert_eq!(
+                violations,
+                vec![Violation::ItemTypeMismatch {
+                    expected: "book".to_string(),
+                    found: "journalArticle".to_string()
+                }]
+            ),
+            other => panic!("expected InvalidItem, got {:?}", other),
+        }
+    }
+}